@@ -0,0 +1,748 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+// `to_binary` is deprecated from cosmwasm-std 1.5 on, but its replacement does not exist in the
+// 1.1 the contract targets
+#[allow(deprecated)]
+use cosmwasm_std::to_binary;
+use cosmwasm_std::{Binary, Deps, DepsMut, Env, Event, MessageInfo, Reply, Response, StdResult, SubMsg, SubMsgResult, WasmMsg};
+use cw2::set_contract_version;
+use mpt::{keccak256, verify_account, verify_storage, U256};
+use verifier::ssz::{concat_gindex, is_valid_merkle_branch, HEADER_STATE_ROOT_INDEX, SLOTS_PER_HISTORICAL_ROOT};
+
+use crate::error::ContractError;
+use crate::message::Message;
+use crate::receipt::verify_sent_message;
+use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, ReceiverExecuteMsg};
+use crate::state::{Config, GasConfig, MessageStatus, PendingMessage, ReceiptProof, CONFIG, MESSAGE_STATUS, NEXT_REPLY_ID, PENDING_MESSAGES, RECEIVERS};
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:target-amb";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+const EXECUTED_MESSAGE: &str = "executed_message";
+
+const DEFAULT_GAS_CONFIG: GasConfig = GasConfig { multiplier: 10, min_gas: 200_000, max_gas: 5_000_000 };
+
+
+/// Handling contract instantiation
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    let owner = match msg.owner {
+        Some(owner) => deps.api.addr_validate(&owner)?,
+        None => info.sender.clone(),
+    };
+    let config = Config {
+        owner,
+        verifier: deps.api.addr_validate(&msg.verifier)?,
+        source_amb: parse_hex("source_amb", &msg.source_amb, Some(20))?,
+        chain_id: msg.chain_id,
+        gas: validate_gas_config(msg.gas.unwrap_or(DEFAULT_GAS_CONFIG))?,
+    };
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "instantiate")
+        .add_attribute("verifier", config.verifier)
+        .add_attribute("source_amb", hex::encode(&config.source_amb))
+        .add_attribute("chain_id", config.chain_id.to_string()))
+}
+
+/// Handling contract execution
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::ExecuteMessage { slot, message, account_proof, storage_proof } => execute::execute_message(
+            deps,
+            slot,
+            parse_hex("message", &message, None)?,
+            parse_proof("account_proof", &account_proof)?,
+            parse_proof("storage_proof", &storage_proof)?,
+        ),
+        ExecuteMsg::ExecuteMessageFromLog { src_slot,
+            tx_slot,
+            message,
+            receipts_root_proof,
+            receipts_root,
+            receipt_proof,
+            tx_index_rlp,
+            log_index, } => execute::execute_message_from_log(deps, src_slot, tx_slot, parse_hex("message", &message, None)?, ReceiptProof {
+                receipts_root_proof: receipts_root_proof.iter()
+                    .map(|node| parse_root("receipts_root_proof", node))
+                    .collect::<Result<_, _>>()?,
+                receipts_root: parse_root("receipts_root", &receipts_root)?,
+                receipt_proof: parse_proof("receipt_proof", &receipt_proof)?,
+                tx_index_rlp: parse_hex("tx_index_rlp", &tx_index_rlp, None)?,
+                log_index: log_index as usize,
+            }),
+        ExecuteMsg::SetReceiver { receiver, contract } => execute::set_receiver(deps, info, receiver, contract),
+        ExecuteMsg::SetGasConfig { gas } => execute::set_gas_config(deps, info, gas),
+    }
+}
+
+pub mod execute {
+    use super::*;
+
+    use verifier::msg::{ExecutionStateRootResponse, HeaderResponse, QueryMsg as VerifierQueryMsg};
+
+    /*
+    * @dev Mirrors `TargetAMB.sol::executeMessage`. The message hash must be stored in the
+    * SourceAMB's `messages[nonce]` slot under the execution state root the verifier holds for
+    * `slot`.
+    */
+    pub fn execute_message(deps: DepsMut, slot: u64, message_bytes: Vec<u8>, account_proof: Vec<Vec<u8>>, storage_proof: Vec<Vec<u8>>) -> Result<Response, ContractError> {
+        let config = CONFIG.load(deps.storage)?;
+        let (message, message_root) = check_message(deps.as_ref(), &config, &message_bytes)?;
+
+        let response: ExecutionStateRootResponse = deps.querier.query_wasm_smart(
+            config.verifier.clone(),
+            &VerifierQueryMsg::ExecutionStateRoot { slot },
+        )?;
+        let execution_state_root = parse_root("execution_state_root", &response.root)?;
+        let account = verify_account(&account_proof, &to_fixed(&config.source_amb), &execution_state_root)?;
+        let slot_value = verify_storage(&storage_proof, &message.storage_key(), &account.storage_root)?;
+        if slot_value != U256::from_big_endian(&message_root) {
+            return Err(ContractError::InvalidMessageHash {});
+        }
+
+        dispatch(deps, message, message_root, Response::new()
+            .add_attribute("action", "execute_message")
+            .add_attribute("slot", slot.to_string()))
+    }
+
+    /*
+    * @dev Mirrors `TargetAMB.sol::executeMessageFromLog`. The receipts root is proven against the
+    * block root the verifier holds for `src_slot`, the receipt against the receipts root,
+    * and the `SentMessage` log in it has to carry the message hash. Transactions older than
+    * SLOTS_PER_HISTORICAL_ROOT need a header proven on the verifier with `ProveHistoricalHeader`.
+    */
+    pub fn execute_message_from_log(deps: DepsMut, src_slot: u64, tx_slot: u64, message_bytes: Vec<u8>, proof: ReceiptProof) -> Result<Response, ContractError> {
+        let config = CONFIG.load(deps.storage)?;
+        let index = receipts_root_index(src_slot, tx_slot)?;
+
+        let response: HeaderResponse = deps.querier.query_wasm_smart(
+            config.verifier.clone(),
+            &VerifierQueryMsg::BlockRoot { slot: src_slot },
+        )?;
+        let header_root = parse_root("header_root", &response.root)?;
+        if !is_valid_merkle_branch(&proof.receipts_root, index, &proof.receipts_root_proof, &header_root) {
+            return Err(ContractError::InvalidReceiptsRootProof {});
+        }
+
+        let (message, message_root) = check_message(deps.as_ref(), &config, &message_bytes)?;
+        let receipt_message_root = verify_sent_message(
+            &proof.receipt_proof,
+            &proof.receipts_root,
+            &proof.tx_index_rlp,
+            proof.log_index,
+            &config.source_amb,
+        )?;
+        if receipt_message_root != message_root {
+            return Err(ContractError::InvalidMessageHash {});
+        }
+
+        dispatch(deps, message, message_root, Response::new()
+            .add_attribute("action", "execute_message_from_log")
+            .add_attribute("src_slot", src_slot.to_string())
+            .add_attribute("tx_slot", tx_slot.to_string()))
+    }
+
+    /*
+    * @dev Decodes a message and checks it has not been executed yet and is addressed to this chain.
+    */
+    fn check_message(deps: Deps, config: &Config, message_bytes: &[u8]) -> Result<(Message, [u8; 32]), ContractError> {
+        let message = Message::decode(message_bytes)?;
+        let message_root = keccak256(message_bytes);
+
+        if MESSAGE_STATUS.may_load(deps.storage, &message_root)?.is_some() {
+            return Err(ContractError::MessageAlreadyExecuted {});
+        }
+        if message.chain_id != config.chain_id {
+            return Err(ContractError::WrongChain { expected: config.chain_id, got: message.chain_id });
+        }
+        Ok((message, message_root))
+    }
+
+    /*
+    * @dev Generalized index of the receipts root relative to the `src_slot` beacon block header:
+    * the `TargetAMB.sol` index into the `src_slot` state, under the header's state root.
+    */
+    fn receipts_root_index(src_slot: u64, tx_slot: u64) -> Result<u64, ContractError> {
+        let index = if tx_slot == src_slot {
+            (32 + 24) * 16 + 3
+        } else if tx_slot.saturating_add(SLOTS_PER_HISTORICAL_ROOT) <= src_slot {
+            return Err(ContractError::TargetSlotTooOld { src_slot, tx_slot });
+        } else if tx_slot < src_slot {
+            let index = (32 + 6) * SLOTS_PER_HISTORICAL_ROOT + tx_slot % SLOTS_PER_HISTORICAL_ROOT;
+            (index * 32 + 24) * 16 + 3
+        } else {
+            return Err(ContractError::InvalidTargetSlot { src_slot, tx_slot });
+        };
+        Ok(concat_gindex(HEADER_STATE_ROOT_INDEX, index))
+    }
+
+    /*
+    * @dev Delivers a verified message to the contract registered for its receiver. The message is
+    * marked failed before its receiver is called so it can not be replayed, the reply upgrades it
+    * to succeeded.
+    */
+    fn dispatch(deps: DepsMut, message: Message, message_root: [u8; 32], response: Response) -> Result<Response, ContractError> {
+        let config = CONFIG.load(deps.storage)?;
+        let receiver = hex::encode(message.receiver);
+        let contract = RECEIVERS.may_load(deps.storage, &message.receiver)?
+            .ok_or(ContractError::UnknownReceiver { receiver: receiver.clone() })?;
+        let gas_limit = receiver_gas_limit(&config.gas, &message.gas_limit);
+
+        MESSAGE_STATUS.save(deps.storage, &message_root, &MessageStatus::ExecutionFailed)?;
+        let reply_id = NEXT_REPLY_ID.may_load(deps.storage)?.unwrap_or_default();
+        NEXT_REPLY_ID.save(deps.storage, &(reply_id + 1))?;
+        PENDING_MESSAGES.save(deps.storage, reply_id, &PendingMessage {
+            message_root: message_root.to_vec(),
+            nonce: hex::encode(message.nonce),
+        })?;
+
+        #[allow(deprecated)]
+        let call = WasmMsg::Execute {
+            contract_addr: contract.to_string(),
+            msg: to_binary(&ReceiverExecuteMsg::ReceiveSuccinct {
+                sender: format!("0x{}", hex::encode(message.sender)),
+                data: Binary::from(message.data),
+            })?,
+            funds: vec![],
+        };
+
+        Ok(response
+            .add_submessage(SubMsg::reply_always(call, reply_id).with_gas_limit(gas_limit))
+            .add_attribute("receiver", receiver)
+            .add_attribute("contract", contract))
+    }
+
+    /*
+    * @dev Routes messages for an Ethereum receiver address to a contract. Only callable by the owner.
+    */
+    pub fn set_receiver(deps: DepsMut, info: MessageInfo, receiver: String, contract: Option<String>) -> Result<Response, ContractError> {
+        let config = CONFIG.load(deps.storage)?;
+        if info.sender != config.owner {
+            return Err(ContractError::Unauthorized {});
+        }
+        let receiver = parse_hex("receiver", &receiver, Some(20))?;
+        match &contract {
+            Some(contract) => {
+                let contract = deps.api.addr_validate(contract)?;
+                RECEIVERS.save(deps.storage, &receiver, &contract)?;
+            }
+            None => RECEIVERS.remove(deps.storage, &receiver),
+        }
+
+        Ok(Response::new()
+            .add_attribute("action", "set_receiver")
+            .add_attribute("receiver", hex::encode(receiver))
+            .add_attribute("contract", contract.unwrap_or_default()))
+    }
+
+    /*
+    * @dev The receiver call's gas limit, which has to fit the u64 CosmWasm meters gas in.
+    */
+    /*
+    * @dev Sets how message gas limits map to receiver call gas. Only callable by the owner.
+    */
+    pub fn set_gas_config(deps: DepsMut, info: MessageInfo, gas: GasConfig) -> Result<Response, ContractError> {
+        let mut config = CONFIG.load(deps.storage)?;
+        if info.sender != config.owner {
+            return Err(ContractError::Unauthorized {});
+        }
+        config.gas = validate_gas_config(gas)?;
+        CONFIG.save(deps.storage, &config)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "set_gas_config")
+            .add_attribute("multiplier", config.gas.multiplier.to_string())
+            .add_attribute("min_gas", config.gas.min_gas.to_string())
+            .add_attribute("max_gas", config.gas.max_gas.to_string()))
+    }
+
+    /*
+    * @dev Converts bytes whose length was checked when they were parsed.
+    */
+    fn to_fixed<const N: usize>(bytes: &[u8]) -> [u8; N] {
+        bytes.try_into().expect("length checked on parse")
+    }
+}
+
+/// Handling contract query
+#[cfg_attr(not(feature = "library"), entry_point)]
+#[allow(deprecated)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&query::get_config(deps)?),
+        QueryMsg::MessageStatus { message_root } => to_binary(&query::get_message_status(message_root, deps)?),
+        QueryMsg::Receiver { receiver } => to_binary(&query::get_receiver(receiver, deps)?),
+    }
+}
+
+pub mod query {
+    use super::*;
+
+    use cosmwasm_std::StdError;
+
+    use crate::msg::{ConfigResponse, MessageStatusResponse, ReceiverResponse};
+
+    pub fn get_config(deps: Deps) -> StdResult<ConfigResponse> {
+        let config = CONFIG.load(deps.storage)?;
+        Ok(ConfigResponse {
+            owner: config.owner,
+            verifier: config.verifier,
+            source_amb: hex::encode(config.source_amb),
+            chain_id: config.chain_id,
+            gas: config.gas,
+        })
+    }
+
+    pub fn get_message_status(message_root: String, deps: Deps) -> StdResult<MessageStatusResponse> {
+        let root = parse_hex("message_root", &message_root, Some(32)).map_err(|err| StdError::generic_err(err.to_string()))?;
+        let status = MESSAGE_STATUS.may_load(deps.storage, &root)?.unwrap_or(MessageStatus::NotExecuted);
+        Ok(MessageStatusResponse { message_root: hex::encode(root), status })
+    }
+
+    pub fn get_receiver(receiver: String, deps: Deps) -> StdResult<ReceiverResponse> {
+        let receiver = parse_hex("receiver", &receiver, Some(20)).map_err(|err| StdError::generic_err(err.to_string()))?;
+        let contract = RECEIVERS.may_load(deps.storage, &receiver)?;
+        Ok(ReceiverResponse { receiver: hex::encode(receiver), contract })
+    }
+}
+
+/// Handling submessage reply.
+/// Records the outcome of a receiver call, mirroring the `ExecutedMessage` event.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    let pending = PENDING_MESSAGES.load(deps.storage, msg.id)?;
+    PENDING_MESSAGES.remove(deps.storage, msg.id);
+
+    let succeeded = matches!(msg.result, SubMsgResult::Ok(_));
+    if succeeded {
+        MESSAGE_STATUS.save(deps.storage, &pending.message_root, &MessageStatus::ExecutionSucceeded)?;
+    }
+
+    Ok(Response::new().add_event(Event::new(EXECUTED_MESSAGE)
+        .add_attribute("nonce", pending.nonce)
+        .add_attribute("message_root", hex::encode(pending.message_root))
+        .add_attribute("status", succeeded.to_string())))
+}
+
+/*
+* @dev Decodes a hex string, with or without `0x` prefix, optionally requiring a length.
+*/
+fn parse_hex(field: &str, value: &str, expected: Option<usize>) -> Result<Vec<u8>, ContractError> {
+    let bytes = hex::decode(value.strip_prefix("0x").unwrap_or(value))
+        .map_err(|_| ContractError::InvalidHex { field: field.to_string() })?;
+    match expected {
+        Some(expected) if bytes.len() != expected => Err(ContractError::InvalidLength {
+            field: field.to_string(),
+            expected,
+            got: bytes.len(),
+        }),
+        _ => Ok(bytes),
+    }
+}
+
+/*
+* @dev Decodes a hex encoded 32 byte root.
+*/
+fn parse_root(field: &str, value: &str) -> Result<[u8; 32], ContractError> {
+    let bytes = parse_hex(field, value, Some(32))?;
+    Ok(bytes.try_into().unwrap())
+}
+
+fn parse_proof(field: &str, proof: &[String]) -> Result<Vec<Vec<u8>>, ContractError> {
+    proof.iter().map(|node| parse_hex(field, node, None)).collect()
+}
+
+fn validate_gas_config(gas: GasConfig) -> Result<GasConfig, ContractError> {
+    if gas.min_gas == 0 || gas.min_gas > gas.max_gas {
+        return Err(ContractError::InvalidGasConfig {});
+    }
+    Ok(gas)
+}
+
+/*
+* @dev Cosmos SDK gas for the receiver call of a message with the given `gasLimit` word. A
+* receiver call always gets at least `min_gas`, so a zero limit does not fail it outright.
+*/
+fn receiver_gas_limit(gas: &GasConfig, word: &[u8; 32]) -> u64 {
+    if word[..24].iter().any(|b| *b != 0) {
+        return gas.max_gas;
+    }
+    let gas_limit = u64::from_be_bytes(word[24..].try_into().unwrap());
+    gas_limit.saturating_mul(gas.multiplier).clamp(gas.min_gas, gas.max_gas)
+}
+
+#[cfg(test)]
+#[allow(deprecated)]
+mod tests {
+    use super::*;
+    use cosmwasm_schema::cw_serde;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage};
+    use cosmwasm_std::{from_binary, from_slice, ContractResult, OwnedDeps, SubMsgResponse, SystemError, SystemResult, WasmQuery};
+
+    use crate::msg::{ConfigResponse, MessageStatusResponse, ReceiverResponse};
+    use verifier::msg::{ExecutionStateRootResponse, HeaderResponse, QueryMsg as VerifierQueryMsg};
+
+    /// `TargetAMB.t.sol::testExecuteMessage`
+    #[cw_serde]
+    struct ExecuteMessageFixture {
+        slot: u64,
+        execution_state_root: String,
+        source_amb: String,
+        chain_id: u16,
+        message: String,
+        account_proof: Vec<String>,
+        storage_proof: Vec<String>,
+    }
+
+    const RECEIVER: &str = "efc56627233b02ea95bae7e19f648d7dcd5bb132";
+
+    fn fixture() -> ExecuteMessageFixture {
+        from_slice(include_bytes!("../testdata/execute_message.json")).unwrap()
+    }
+
+    fn execute_msg(fixture: &ExecuteMessageFixture) -> ExecuteMsg {
+        ExecuteMsg::ExecuteMessage {
+            slot: fixture.slot,
+            message: fixture.message.clone(),
+            account_proof: fixture.account_proof.clone(),
+            storage_proof: fixture.storage_proof.clone(),
+        }
+    }
+
+    /// `TargetAMB.t.sol::testExecuteMessageFromLog`, with the state root branch extended to a
+    /// beacon block header holding that state root
+    #[cw_serde]
+    struct ExecuteMessageFromLogFixture {
+        src_slot: u64,
+        tx_slot: u64,
+        header_root: String,
+        source_amb: String,
+        chain_id: u16,
+        message: String,
+        receipts_root_proof: Vec<String>,
+        receipts_root: String,
+        receipt_proof: Vec<String>,
+        tx_index_rlp: String,
+        log_index: u32,
+    }
+
+    const LOG_RECEIVER: &str = "166ea4529ae9b7ec27263afe75a8897a805ddb26";
+
+    fn log_fixture() -> ExecuteMessageFromLogFixture {
+        from_slice(include_bytes!("../testdata/execute_message_from_log.json")).unwrap()
+    }
+
+    fn execute_from_log_msg(fixture: &ExecuteMessageFromLogFixture) -> ExecuteMsg {
+        ExecuteMsg::ExecuteMessageFromLog {
+            src_slot: fixture.src_slot,
+            tx_slot: fixture.tx_slot,
+            message: fixture.message.clone(),
+            receipts_root_proof: fixture.receipts_root_proof.clone(),
+            receipts_root: fixture.receipts_root.clone(),
+            receipt_proof: fixture.receipt_proof.clone(),
+            tx_index_rlp: fixture.tx_index_rlp.clone(),
+            log_index: fixture.log_index,
+        }
+    }
+
+    /*
+    * @dev Instantiates against a mocked verifier holding `root` for the fixture slot and
+    * routes the fixture receiver to `receiver_contract`.
+    */
+    fn setup(fixture: &ExecuteMessageFixture, root: String) -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        setup_with(&fixture.source_amb, fixture.chain_id, RECEIVER, vec![(fixture.slot, root)], vec![])
+    }
+
+    fn setup_from_log(fixture: &ExecuteMessageFromLogFixture, header_root: String) -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        setup_with(&fixture.source_amb, fixture.chain_id, LOG_RECEIVER, vec![], vec![(fixture.src_slot, header_root)])
+    }
+
+    /*
+    * @dev Instantiates against a mocked verifier serving the given execution state roots and
+    * header roots by slot, and routes `receiver` to `receiver_contract`.
+    */
+    fn setup_with(source_amb: &str, chain_id: u16, receiver: &str, execution_state_roots: Vec<(u64, String)>, headers: Vec<(u64, String)>) -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies();
+        deps.querier.update_wasm(move |query| match query {
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == "verifier" => {
+                let lookup = |roots: &[(u64, String)], slot: u64| roots.iter()
+                    .find(|(stored, _)| *stored == slot)
+                    .map(|(_, root)| root.clone());
+                let response = match from_binary(msg).unwrap() {
+                    VerifierQueryMsg::ExecutionStateRoot { slot } => lookup(&execution_state_roots, slot)
+                        .map(|root| to_binary(&ExecutionStateRootResponse { slot, root }).unwrap()),
+                    VerifierQueryMsg::BlockRoot { slot } => lookup(&headers, slot)
+                        .map(|root| to_binary(&HeaderResponse { slot, root }).unwrap()),
+                    _ => None,
+                };
+                match response {
+                    Some(response) => SystemResult::Ok(ContractResult::Ok(response)),
+                    None => SystemResult::Ok(ContractResult::Err("not found".to_string())),
+                }
+            }
+            _ => SystemResult::Err(SystemError::UnsupportedRequest { kind: "wasm".to_string() }),
+        });
+
+        let msg = InstantiateMsg {
+            verifier: "verifier".to_string(),
+            source_amb: source_amb.to_string(),
+            chain_id,
+            owner: None,
+            gas: None,
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let msg = ExecuteMsg::SetReceiver { receiver: receiver.to_string(), contract: Some("receiver_contract".to_string()) };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        deps
+    }
+
+    fn message_root(fixture: &ExecuteMessageFixture) -> String {
+        root_of(&fixture.message)
+    }
+
+    fn root_of(message: &str) -> String {
+        hex::encode(keccak256(&hex::decode(message.trim_start_matches("0x")).unwrap()))
+    }
+
+    fn status(deps: Deps, root: &str) -> MessageStatus {
+        let res = query(deps, mock_env(), QueryMsg::MessageStatus { message_root: root.to_string() }).unwrap();
+        let value: MessageStatusResponse = from_binary(&res).unwrap();
+        value.status
+    }
+
+    #[test]
+    fn proper_initialization() {
+        let fixture = fixture();
+        let deps = setup(&fixture, fixture.execution_state_root.clone());
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+        let value: ConfigResponse = from_binary(&res).unwrap();
+        assert_eq!("creator", value.owner.as_str());
+        assert_eq!("verifier", value.verifier.as_str());
+        assert_eq!("42793df05c085187e20aa99104a4e67e21823880", value.source_amb);
+        assert_eq!(100, value.chain_id);
+        assert_eq!(DEFAULT_GAS_CONFIG, value.gas);
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Receiver { receiver: format!("0x{}", RECEIVER) }).unwrap();
+        let value: ReceiverResponse = from_binary(&res).unwrap();
+        assert_eq!(Some("receiver_contract".to_string()), value.contract.map(|addr| addr.to_string()));
+    }
+
+    #[test]
+    fn execute_message() {
+        let fixture = fixture();
+        let mut deps = setup(&fixture, fixture.execution_state_root.clone());
+        let root = message_root(&fixture);
+        assert_eq!(MessageStatus::NotExecuted, status(deps.as_ref(), &root));
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("relayer", &[]), execute_msg(&fixture)).unwrap();
+        assert_eq!(1, res.messages.len());
+        let submsg = &res.messages[0];
+        assert_eq!(Some(500000), submsg.gas_limit);
+        match &submsg.msg {
+            cosmwasm_std::CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, msg, .. }) => {
+                assert_eq!("receiver_contract", contract_addr);
+                let ReceiverExecuteMsg::ReceiveSuccinct { sender, data } = from_binary(msg).unwrap();
+                assert_eq!("0x6e57b45b57e84c964e7cdff596ed02e0387d617e", sender);
+                assert_eq!(96, data.len());
+            }
+            other => panic!("unexpected message {:?}", other),
+        }
+        assert_eq!(MessageStatus::ExecutionFailed, status(deps.as_ref(), &root));
+
+        let reply_msg = Reply {
+            id: submsg.id,
+            result: SubMsgResult::Ok(SubMsgResponse { events: vec![], data: None }),
+        };
+        let res = reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+        assert_eq!(EXECUTED_MESSAGE, res.events[0].ty);
+        assert_eq!(MessageStatus::ExecutionSucceeded, status(deps.as_ref(), &root));
+
+        // Replays are rejected whatever the outcome of the first execution
+        let err = execute(deps.as_mut(), mock_env(), mock_info("relayer", &[]), execute_msg(&fixture)).unwrap_err();
+        assert!(matches!(err, ContractError::MessageAlreadyExecuted {}));
+    }
+
+    #[test]
+    fn receiver_gas_limit() {
+        let gas = GasConfig { multiplier: 10, min_gas: 200_000, max_gas: 5_000_000 };
+        let word = |gas_limit: u64| {
+            let mut word = [0u8; 32];
+            word[24..].copy_from_slice(&gas_limit.to_be_bytes());
+            word
+        };
+        assert_eq!(200_000, super::receiver_gas_limit(&gas, &word(0)));
+        assert_eq!(200_000, super::receiver_gas_limit(&gas, &word(1000)));
+        assert_eq!(500_000, super::receiver_gas_limit(&gas, &word(50000)));
+        assert_eq!(5_000_000, super::receiver_gas_limit(&gas, &word(u64::MAX)));
+        assert_eq!(5_000_000, super::receiver_gas_limit(&gas, &[0xff; 32]));
+
+        // The owner tunes the mapping, receiver calls use it right away
+        let fixture = fixture();
+        let mut deps = setup(&fixture, fixture.execution_state_root.clone());
+        let set_gas = |multiplier, min_gas, max_gas| ExecuteMsg::SetGasConfig { gas: GasConfig { multiplier, min_gas, max_gas } };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), set_gas(1, 1, 1)).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        for (min_gas, max_gas) in [(0, 100_000), (200_000, 100_000)] {
+            let err = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), set_gas(1, min_gas, max_gas)).unwrap_err();
+            assert!(matches!(err, ContractError::InvalidGasConfig {}));
+        }
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), set_gas(2, 10_000, 80_000)).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), mock_info("relayer", &[]), execute_msg(&fixture)).unwrap();
+        assert_eq!(Some(80_000), res.messages[0].gas_limit);
+    }
+
+    #[test]
+    fn failed_receiver_call() {
+        let fixture = fixture();
+        let mut deps = setup(&fixture, fixture.execution_state_root.clone());
+        let root = message_root(&fixture);
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("relayer", &[]), execute_msg(&fixture)).unwrap();
+        let reply_msg = Reply { id: res.messages[0].id, result: SubMsgResult::Err("out of gas".to_string()) };
+        let res = reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+        assert_eq!("false", res.events[0].attributes[2].value);
+        assert_eq!(MessageStatus::ExecutionFailed, status(deps.as_ref(), &root));
+
+        let err = execute(deps.as_mut(), mock_env(), mock_info("relayer", &[]), execute_msg(&fixture)).unwrap_err();
+        assert!(matches!(err, ContractError::MessageAlreadyExecuted {}));
+    }
+
+    #[test]
+    fn invalid_proofs() {
+        let fixture = fixture();
+
+        // Proof against a different execution state root
+        let mut deps = setup(&fixture, hex::encode([1u8; 32]));
+        let err = execute(deps.as_mut(), mock_env(), mock_info("relayer", &[]), execute_msg(&fixture)).unwrap_err();
+        assert!(matches!(err, ContractError::Proof(_)));
+
+        // No execution state root for the slot
+        let mut deps = setup(&fixture, fixture.execution_state_root.clone());
+        let mut msg = fixture.clone();
+        msg.slot += 1;
+        let err = execute(deps.as_mut(), mock_env(), mock_info("relayer", &[]), execute_msg(&msg)).unwrap_err();
+        assert!(matches!(err, ContractError::Std(_)));
+
+        // Tampered storage proof
+        let mut msg = fixture.clone();
+        msg.storage_proof[2] = msg.storage_proof[2].replace("e1", "e2");
+        let err = execute(deps.as_mut(), mock_env(), mock_info("relayer", &[]), execute_msg(&msg)).unwrap_err();
+        assert!(matches!(err, ContractError::Proof(_)));
+
+        // A message whose hash is not the stored one, here with a different gas limit
+        let mut msg = fixture.clone();
+        msg.message = msg.message.replace("c350", "c351");
+        let err = execute(deps.as_mut(), mock_env(), mock_info("relayer", &[]), execute_msg(&msg)).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidMessageHash {}));
+    }
+
+    #[test]
+    fn wrong_chain_and_unknown_receiver() {
+        let mut fixture = fixture();
+        fixture.chain_id = 10;
+        let mut deps = setup(&fixture, fixture.execution_state_root.clone());
+        let err = execute(deps.as_mut(), mock_env(), mock_info("relayer", &[]), execute_msg(&fixture)).unwrap_err();
+        assert!(matches!(err, ContractError::WrongChain { expected: 10, got: 100 }));
+
+        let fixture = self::fixture();
+        let mut deps = setup(&fixture, fixture.execution_state_root.clone());
+        let unroute = ExecuteMsg::SetReceiver { receiver: RECEIVER.to_string(), contract: None };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), unroute.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), unroute).unwrap();
+        let err = execute(deps.as_mut(), mock_env(), mock_info("relayer", &[]), execute_msg(&fixture)).unwrap_err();
+        assert!(matches!(err, ContractError::UnknownReceiver { .. }));
+        assert_eq!(MessageStatus::NotExecuted, status(deps.as_ref(), &message_root(&fixture)));
+    }
+
+    #[test]
+    fn execute_message_from_log() {
+        let fixture = log_fixture();
+        let mut deps = setup_from_log(&fixture, fixture.header_root.clone());
+        let root = root_of(&fixture.message);
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("relayer", &[]), execute_from_log_msg(&fixture)).unwrap();
+        assert_eq!(1, res.messages.len());
+        let submsg = &res.messages[0];
+        assert_eq!(Some(1000000), submsg.gas_limit);
+        match &submsg.msg {
+            cosmwasm_std::CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, msg, .. }) => {
+                assert_eq!("receiver_contract", contract_addr);
+                let ReceiverExecuteMsg::ReceiveSuccinct { sender, .. } = from_binary(msg).unwrap();
+                assert_eq!("0x76f2b20a94385fcf7c8bfd79c6bc74db4cd11e59", sender);
+            }
+            other => panic!("unexpected message {:?}", other),
+        }
+
+        let reply_msg = Reply {
+            id: submsg.id,
+            result: SubMsgResult::Ok(SubMsgResponse { events: vec![], data: None }),
+        };
+        reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+        assert_eq!(MessageStatus::ExecutionSucceeded, status(deps.as_ref(), &root));
+
+        let err = execute(deps.as_mut(), mock_env(), mock_info("relayer", &[]), execute_from_log_msg(&fixture)).unwrap_err();
+        assert!(matches!(err, ContractError::MessageAlreadyExecuted {}));
+    }
+
+    #[test]
+    fn invalid_receipt_proofs() {
+        let fixture = log_fixture();
+        let run = |deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>, fixture: &ExecuteMessageFromLogFixture| {
+            execute(deps.as_mut(), mock_env(), mock_info("relayer", &[]), execute_from_log_msg(fixture)).unwrap_err()
+        };
+
+        // Branch against another header
+        let mut deps = setup_from_log(&fixture, hex::encode([1u8; 32]));
+        assert!(matches!(run(&mut deps, &fixture), ContractError::InvalidReceiptsRootProof {}));
+
+        let mut deps = setup_from_log(&fixture, fixture.header_root.clone());
+
+        // Transaction slot after the source slot, or older than the state's state_roots vector
+        let mut msg = fixture.clone();
+        msg.tx_slot = msg.src_slot + 1;
+        assert!(matches!(run(&mut deps, &msg), ContractError::InvalidTargetSlot { .. }));
+        msg.tx_slot = msg.src_slot - 8192;
+        assert!(matches!(run(&mut deps, &msg), ContractError::TargetSlotTooOld { .. }));
+
+        // Branch for another transaction slot
+        let mut msg = fixture.clone();
+        msg.tx_slot -= 1;
+        assert!(matches!(run(&mut deps, &msg), ContractError::InvalidReceiptsRootProof {}));
+
+        // Receipt without that many logs
+        let mut msg = fixture.clone();
+        msg.log_index = 100;
+        assert!(matches!(run(&mut deps, &msg), ContractError::LogIndexOutOfBounds { log_index: 100 }));
+
+        // Receipt for another transaction
+        let mut msg = fixture.clone();
+        msg.tx_index_rlp = "0x28".to_string();
+        assert!(matches!(run(&mut deps, &msg), ContractError::Proof(_) | ContractError::InvalidReceipt { .. }));
+
+        // Message that does not match the logged hash
+        let mut msg = fixture.clone();
+        msg.message = msg.message.replace("186a0", "186a1");
+        assert!(matches!(run(&mut deps, &msg), ContractError::InvalidMessageHash {}));
+
+        // Log emitted by another contract than the configured SourceAMB
+        let mut deps = setup_with(&fixture.source_amb.replace("9FAC", "9FAD"), fixture.chain_id, LOG_RECEIVER, vec![], vec![(fixture.src_slot, fixture.header_root.clone())]);
+        assert!(matches!(run(&mut deps, &fixture), ContractError::InvalidEmitter { .. }));
+    }
+}
@@ -0,0 +1,61 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+use mpt::ProofError;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Invalid hex string for {field}")]
+    InvalidHex { field: String },
+
+    #[error("Invalid length for {field}: expected {expected} bytes, got {got}")]
+    InvalidLength { field: String, expected: usize, got: usize },
+
+    #[error("Malformed message: {reason}")]
+    InvalidMessage { reason: String },
+
+    #[error("Message already executed.")]
+    MessageAlreadyExecuted {},
+
+    #[error("Wrong chain: message is for chain {got}, this is chain {expected}")]
+    WrongChain { expected: u16, got: u16 },
+
+    #[error("Invalid message hash.")]
+    InvalidMessageHash {},
+
+    #[error("No contract is registered for receiver {receiver}")]
+    UnknownReceiver { receiver: String },
+
+    #[error("Invalid gas config: min_gas must be positive and at most max_gas")]
+    InvalidGasConfig {},
+
+    #[error("Source slot {src_slot} is more than SLOTS_PER_HISTORICAL_ROOT after transaction slot {tx_slot}, prove an older header on the verifier first")]
+    TargetSlotTooOld { src_slot: u64, tx_slot: u64 },
+
+    #[error("Invalid target slot: transaction slot {tx_slot} is after source slot {src_slot}")]
+    InvalidTargetSlot { src_slot: u64, tx_slot: u64 },
+
+    #[error("Invalid receipts root proof.")]
+    InvalidReceiptsRootProof {},
+
+    #[error("Malformed receipt: {reason}")]
+    InvalidReceipt { reason: String },
+
+    #[error("Log index {log_index} out of bounds")]
+    LogIndexOutOfBounds { log_index: usize },
+
+    #[error("Invalid event emitter {emitter}")]
+    InvalidEmitter { emitter: String },
+
+    #[error("Different event signature expected.")]
+    InvalidEventSignature {},
+
+    #[error("{0}")]
+    Proof(#[from] ProofError),
+}
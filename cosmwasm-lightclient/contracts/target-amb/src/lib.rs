@@ -0,0 +1,8 @@
+pub mod contract;
+mod error;
+pub mod msg;
+pub mod state;
+pub mod message;
+pub mod receipt;
+
+pub use crate::error::ContractError;
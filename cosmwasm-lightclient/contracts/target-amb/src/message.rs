@@ -0,0 +1,75 @@
+use crate::error::ContractError;
+
+/// A `SourceAMB` message, decoded from
+/// `abi.encode(uint256 nonce, address sender, address receiver, uint16 chainId, uint256 gasLimit, bytes data)`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    pub nonce: [u8; 32],
+    pub sender: [u8; 20],
+    pub receiver: [u8; 20],
+    pub chain_id: u16,
+    pub gas_limit: [u8; 32],
+    pub data: Vec<u8>,
+}
+
+const WORD: usize = 32;
+const HEAD_WORDS: usize = 6;
+
+fn invalid(reason: &str) -> ContractError {
+    ContractError::InvalidMessage { reason: reason.to_string() }
+}
+
+fn word(bytes: &[u8], index: usize) -> Result<&[u8], ContractError> {
+    bytes.get(index * WORD..(index + 1) * WORD).ok_or_else(|| invalid("truncated head"))
+}
+
+/*
+* @dev Reads a word that must hold a value of at most `size` bytes, left padded with zeros.
+*/
+fn word_of_size(bytes: &[u8], index: usize, size: usize) -> Result<&[u8], ContractError> {
+    let word = word(bytes, index)?;
+    if word[..WORD - size].iter().any(|b| *b != 0) {
+        return Err(invalid("dirty padding"));
+    }
+    Ok(&word[WORD - size..])
+}
+
+fn word_as_usize(bytes: &[u8], index: usize) -> Result<usize, ContractError> {
+    let value = word_of_size(bytes, index, 8)?;
+    usize::try_from(u64::from_be_bytes(value.try_into().unwrap())).map_err(|_| invalid("offset overflow"))
+}
+
+impl Message {
+    /*
+    * @dev Mirrors the `abi.decode` in `TargetAMB.sol::executeMessage`.
+    */
+    pub fn decode(bytes: &[u8]) -> Result<Message, ContractError> {
+        let offset = word_as_usize(bytes, 5)?;
+        if offset < HEAD_WORDS * WORD || offset % WORD != 0 {
+            return Err(invalid("bad data offset"));
+        }
+        let tail = bytes.get(offset..).ok_or_else(|| invalid("truncated data"))?;
+        let len = word_as_usize(tail, 0)?;
+        let data = tail.get(WORD..WORD.checked_add(len).ok_or_else(|| invalid("bad data length"))?)
+            .ok_or_else(|| invalid("truncated data"))?;
+
+        Ok(Message {
+            nonce: word(bytes, 0)?.try_into().unwrap(),
+            sender: word_of_size(bytes, 1, 20)?.try_into().unwrap(),
+            receiver: word_of_size(bytes, 2, 20)?.try_into().unwrap(),
+            chain_id: u16::from_be_bytes(word_of_size(bytes, 3, 2)?.try_into().unwrap()),
+            gas_limit: word(bytes, 4)?.try_into().unwrap(),
+            data: data.to_vec(),
+        })
+    }
+
+    /*
+    * @dev The `SourceAMB` storage slot holding this message's hash:
+    * `keccak256(abi.encode(nonce, 0))`, i.e. `messages[nonce]` for the mapping at slot 0.
+    */
+    pub fn storage_key(&self) -> [u8; 32] {
+        let mut preimage = [0u8; 64];
+        preimage[..32].copy_from_slice(&self.nonce);
+        mpt::keccak256(&preimage)
+    }
+}
@@ -0,0 +1,113 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, Binary};
+
+use crate::state::{GasConfig, MessageStatus};
+
+/// Message type for `instantiate` entry_point
+#[cw_serde]
+pub struct InstantiateMsg {
+    /// Light client contract providing verified execution state roots
+    pub verifier: String,
+    /// Hex encoded address of the SourceAMB contract on Ethereum
+    pub source_amb: String,
+    /// Chain id messages must be addressed to
+    pub chain_id: u16,
+    /// Owner allowed to manage receivers, defaults to the instantiator
+    pub owner: Option<String>,
+    /// Mapping of message gas limits to receiver call gas, defaults to a multiplier of 10
+    /// between 200k and 5M gas
+    pub gas: Option<GasConfig>,
+}
+
+/// Message type for 'execute' entry_point
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Mirrors `TargetAMB.sol::executeMessage`. Proves the message hash is stored by the
+    /// SourceAMB at the execution state root of `slot` and delivers it to its receiver.
+    ExecuteMessage {
+        slot: u64,
+        /// Hex encoded `abi.encode(nonce, sender, receiver, chainId, gasLimit, data)`
+        message: String,
+        /// Hex encoded RLP nodes of the SourceAMB account proof, as returned by `eth_getProof`
+        account_proof: Vec<String>,
+        /// Hex encoded RLP nodes of the storage proof for the message slot
+        storage_proof: Vec<String>,
+    },
+    /// Mirrors `TargetAMB.sol::executeMessageFromLog`. Proves the `SentMessage` log emitted by the
+    /// SourceAMB in a transaction at `tx_slot` against the block root the verifier holds for
+    /// `src_slot`, verified or proven with ProveHistoricalHeader, then delivers the message to
+    /// its receiver.
+    ExecuteMessageFromLog {
+        src_slot: u64,
+        tx_slot: u64,
+        /// Hex encoded `abi.encode(nonce, sender, receiver, chainId, gasLimit, data)`
+        message: String,
+        /// Hex encoded SSZ branch from the receipts root to the beacon block header root: the
+        /// `TargetAMB.sol` branch to the `src_slot` state root, followed by the header's state root branch
+        receipts_root_proof: Vec<String>,
+        /// Hex encoded receipts root of the execution payload at `tx_slot`
+        receipts_root: String,
+        /// Hex encoded RLP nodes of the receipt proof against the receipts root
+        receipt_proof: Vec<String>,
+        /// Hex encoded RLP of the transaction index, the receipt's key in the receipts trie
+        tx_index_rlp: String,
+        log_index: u32,
+    },
+    /// Owner only. Routes messages for an Ethereum receiver address to a contract, or
+    /// stops routing them when `contract` is None.
+    SetReceiver {
+        receiver: String,
+        contract: Option<String>,
+    },
+    /// Owner only. Sets how message gas limits map to the Cosmos SDK gas of receiver calls.
+    SetGasConfig {
+        gas: GasConfig,
+    },
+}
+
+/// Message delivered to receiver contracts
+#[cw_serde]
+pub enum ReceiverExecuteMsg {
+    ReceiveSuccinct {
+        /// Hex encoded Ethereum address of the message sender
+        sender: String,
+        data: Binary,
+    },
+}
+
+/// Message type for `query` entry_point
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    // Config gets the verifier, SourceAMB and chain the contract accepts messages from
+    #[returns(ConfigResponse)]
+    Config {},
+    // MessageStatus gets the execution status of a hex encoded message root
+    #[returns(MessageStatusResponse)]
+    MessageStatus {message_root: String},
+    // Receiver gets the contract messages for an Ethereum receiver are delivered to
+    #[returns(ReceiverResponse)]
+    Receiver {receiver: String},
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+    pub owner: Addr,
+    pub verifier: Addr,
+    /// Hex encoded SourceAMB address
+    pub source_amb: String,
+    pub chain_id: u16,
+    pub gas: GasConfig,
+}
+
+#[cw_serde]
+pub struct MessageStatusResponse {
+    pub message_root: String,
+    pub status: MessageStatus,
+}
+
+#[cw_serde]
+pub struct ReceiverResponse {
+    pub receiver: String,
+    pub contract: Option<Addr>,
+}
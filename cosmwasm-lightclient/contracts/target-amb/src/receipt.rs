@@ -0,0 +1,49 @@
+use mpt::{extract_proof_value, keccak256, Rlp};
+
+use crate::error::ContractError;
+
+pub const SENT_MESSAGE_SIGNATURE: &[u8] = b"SentMessage(uint256,bytes32,bytes)";
+
+fn invalid(reason: &str) -> ContractError {
+    ContractError::InvalidReceipt { reason: reason.to_string() }
+}
+
+/*
+* @dev Mirrors `MPT.verifyAMBReceipt`. Proves the receipt at `tx_index_rlp` under
+* `receipts_root`, then returns the `msgHash` topic of its `log_index`th log, which has to be
+* a `SentMessage` emitted by `emitter`.
+*/
+pub fn verify_sent_message(
+    proof: &[Vec<u8>],
+    receipts_root: &[u8; 32],
+    tx_index_rlp: &[u8],
+    log_index: usize,
+    emitter: &[u8],
+) -> Result<[u8; 32], ContractError> {
+    let value = extract_proof_value(receipts_root, tx_index_rlp, proof)?
+        .ok_or_else(|| invalid("receipt not found"))?;
+    // Typed receipts are prefixed with their EIP-2718 transaction type
+    let encoded = match value.first() {
+        Some(tx_type) if *tx_type < 0x80 => &value[1..],
+        _ => &value[..],
+    };
+    let receipt = Rlp::decode(encoded)?;
+    let [_, _, _, logs] = receipt.as_list()? else {
+        return Err(invalid("invalid receipt length"));
+    };
+    let log = logs.as_list()?.get(log_index)
+        .ok_or(ContractError::LogIndexOutOfBounds { log_index })?;
+    let [address, topics, _] = log.as_list()? else {
+        return Err(invalid("log has incorrect number of fields"));
+    };
+
+    if address.as_bytes()? != emitter {
+        return Err(ContractError::InvalidEmitter { emitter: hex::encode(address.as_bytes()?) });
+    }
+    match topics.as_list()? {
+        [signature, _, message_root] if signature.as_bytes()? == keccak256(SENT_MESSAGE_SIGNATURE) => {
+            message_root.as_bytes()?.try_into().map_err(|_| invalid("invalid message root topic"))
+        }
+        _ => Err(ContractError::InvalidEventSignature {}),
+    }
+}
@@ -0,0 +1,67 @@
+// use `cw_storage_plus` to create ORM-like interface to storage
+// see: https://crates.io/crates/cw-storage-plus
+use cosmwasm_std::Addr;
+use cosmwasm_schema::cw_serde;
+
+use cw_storage_plus::{Item, Map};
+
+#[cw_serde]
+pub struct Config {
+    // Authority allowed to manage receivers
+    pub owner: Addr,
+    // Light client the execution state roots are read from
+    pub verifier: Addr,
+    // Address of the SourceAMB contract on Ethereum
+    pub source_amb: Vec<u8>,
+    // Chain id messages must be addressed to
+    pub chain_id: u16,
+    // Cosmos SDK gas given to receiver calls
+    pub gas: GasConfig,
+}
+
+/// Maps the Ethereum `gasLimit` of a message to the Cosmos SDK gas its receiver call gets.
+/// The two are not comparable, so the limit is scaled by `multiplier` and clamped to
+/// `[min_gas, max_gas]`; a zero limit gets `min_gas` and one beyond a u64 gets `max_gas`.
+#[cw_serde]
+pub struct GasConfig {
+    pub multiplier: u64,
+    pub min_gas: u64,
+    pub max_gas: u64,
+}
+
+/// Mirrors `MessageStatus` in `IAMB.sol`
+#[cw_serde]
+pub enum MessageStatus {
+    NotExecuted,
+    ExecutionFailed,
+    ExecutionSucceeded,
+}
+
+/// Message awaiting the reply of its receiver call
+#[cw_serde]
+pub struct PendingMessage {
+    pub message_root: Vec<u8>,
+    pub nonce: String,
+}
+
+/// Parsed proof of a `SentMessage` log, see `ExecuteMsg::ExecuteMessageFromLog`
+#[cw_serde]
+pub struct ReceiptProof {
+    pub receipts_root_proof: Vec<[u8; 32]>,
+    pub receipts_root: [u8; 32],
+    pub receipt_proof: Vec<Vec<u8>>,
+    pub tx_index_rlp: Vec<u8>,
+    pub log_index: usize,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+
+// Keyed by message root, missing entries have not been executed
+pub const MESSAGE_STATUS: Map<&[u8], MessageStatus> = Map::new("message_status");
+
+// Ethereum receiver address to the contract its messages are delivered to
+pub const RECEIVERS: Map<&[u8], Addr> = Map::new("receivers");
+
+// Messages awaiting the reply of their receiver call, keyed by reply id
+pub const PENDING_MESSAGES: Map<u64, PendingMessage> = Map::new("pending_messages");
+pub const NEXT_REPLY_ID: Item<u64> = Item::new("next_reply_id");
@@ -1,16 +1,23 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response, StdResult, Uint256};
-use cw2::set_contract_version;
+// `to_binary` is deprecated from cosmwasm-std 1.5 on, but its replacement does not exist in the
+// 1.1 the contract targets
+#[allow(deprecated)]
+use cosmwasm_std::to_binary;
+use cosmwasm_std::{Addr, BankMsg, Binary, Coin, ConversionOverflowError, Deps, DepsMut, Empty, Env, Event, MessageInfo, Order, Reply, Response, StdError, StdResult, Uint128, Uint256};
+use cw2::{get_contract_version, set_contract_version};
 
-use sha2::{Digest, Sha256};
 use std::str::{FromStr};
 
 
-use crate::state::{STATE, State, CircomProof, Groth16Proof, LightClientStep, LightClientRotate, PublicSignals, HEADERS, EXECUTION_STATE_ROOTS, SYNC_COMMITTEE_POSEIDONS, BEST_UPDATES};
+use crate::state::{STATE, State, PAUSED, REWARD_CONFIG, RewardConfig, RELAYER_ALLOWLIST_ENABLED, RELAYERS, INCONSISTENCY_EVIDENCE, InconsistencyEvidence, InconsistencyKind, CircomProof, Groth16Proof, LightClientStep, LightClientRotate, PublicSignals, HEADERS, EXECUTION_STATE_ROOTS, SYNC_COMMITTEE_POSEIDONS, BEST_UPDATES, HISTORICAL_BLOCK_ROOTS, STEP_PREPARED_VERIFYING_KEY, ROTATE_PREPARED_VERIFYING_KEY, STEP_VERIFYING_KEY_HISTORY, ROTATE_VERIFYING_KEY_HISTORY, VerifyingKeyVersion};
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
-use crate::helpers::Verifier;
+use crate::msg::{Circuit, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
+use crate::migrations::{parse_version, run_migrations, MIGRATIONS};
+use crate::helpers::{step_commitment, Verifier, VerifyingKeyJson};
+use crate::events;
+use crate::ssz::{block_roots_index, concat_gindex, historical_block_root_index, is_valid_merkle_branch, HEADER_STATE_ROOT_INDEX, SLOTS_PER_HISTORICAL_ROOT};
+use cw_storage_plus::{Bound, Map};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:verifier";
@@ -18,9 +25,8 @@ const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 const MIN_SYNC_COMMITTEE_PARTICIPANTS: u64 = 10;
 const SYNC_COMMITTEE_SIZE: u64 = 512;
-// const FINALIZED_ROOT_INDEX: u64 = 105;
-// const NEXT_SYNC_COMMITTEE_SIZE: u64 = 55;
-// const EXECUTION_STATE_ROOT_INDEX: u64 = 402;
+const STEP_PUBLIC_INPUTS: u32 = 1;
+const ROTATE_PUBLIC_INPUTS: u32 = 65;
 
 /// Handling contract instantiation
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -32,52 +38,76 @@ pub fn instantiate(
 ) -> Result<Response, ContractError> {
 
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    // The slot clock and sync committee periods divide by these
+    for (field, value) in [("seconds_per_slot", msg.seconds_per_slot), ("slots_per_period", msg.slots_per_period)] {
+        if value == 0 {
+            return Err(ContractError::ZeroValue { field: field.to_string() });
+        }
+    }
+    let owner = match msg.owner {
+        Some(owner) => deps.api.addr_validate(&owner)?,
+        None => info.sender.clone(),
+    };
     let state: State = State {
-        genesis_validators_root: hex::decode(msg.genesis_validators_root).unwrap(),
+        genesis_validators_root: parse_root("genesis_validators_root", &msg.genesis_validators_root)?,
         genesis_time: Uint256::from(msg.genesis_time),
         seconds_per_slot: Uint256::from(msg.seconds_per_slot),
         slots_per_period: Uint256::from(msg.slots_per_period),
 
         consistent: true,
         head: Uint256::from(0u64),
+        head_execution_state_root: vec![0; 32],
+        max_head_lag: msg.max_head_lag.map(Uint256::from),
+        max_future_slots: Uint256::from(msg.max_future_slots.unwrap_or(0)),
+        capella_fork_slot: msg.capella_fork_slot.map(Uint256::from),
 
-
+        owner: Some(owner),
+        pending_owner: None,
     };
     STATE.save(deps.storage, &state)?;
 
-    // TODO: Propogate error up
-    let _response = set_sync_committee_poseidon(deps.branch(), Uint256::from(msg.sync_committee_period), Uint256::from_str(&msg.sync_committee_poseidon).unwrap().to_le_bytes().to_vec());
-
+    set_verifying_key(deps.branch(), _env.block.height, &Circuit::Step, msg.step_verifying_key)?;
+    set_verifying_key(deps.branch(), _env.block.height, &Circuit::Rotate, msg.rotate_verifying_key)?;
 
+    let sync_committee_poseidon = parse_poseidon("sync_committee_poseidon", &msg.sync_committee_poseidon)?;
+    let event = set_sync_committee_poseidon(deps.branch(), &info.sender, Uint256::from(msg.sync_committee_period), sync_committee_poseidon)?.into_event();
 
     // TOOD: Update response string
     Ok(Response::new()
+        .add_event(event)
         .add_attribute("method", "instantiate")
         .add_attribute("caller (operator)", info.sender)
         .add_attribute("count", msg.genesis_time.to_string()))
 }
 
-// /// Handling contract migration
-// /// To make a contract migratable, you need
-// /// - this entry_point implemented
-// /// - only contract admin can migrate, so admin has to be set at contract initiation time
-// /// Handling contract execution
-// #[cfg_attr(not(feature = "library"), entry_point)]
-// pub fn migrate(_deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
-//     match msg {
-//         // Find matched incoming message variant and execute them with your custom logic.
-//         //
-//         // With `Response` type, it is possible to dispatch message to invoke external logic.
-//         // See: https://github.com/CosmWasm/cosmwasm/blob/main/SEMANTICS.md#dispatching-messages
-//     }
-// }
+/// Handling contract migration
+/// Refuses to migrate from another contract or from a newer version, then runs every
+/// pending storage migration step in order before recording the new version.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(mut deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    let stored = get_contract_version(deps.storage)?;
+    if stored.contract != CONTRACT_NAME {
+        return Err(ContractError::CannotMigrate { contract: stored.contract });
+    }
+
+    let from = parse_version(&stored.version)?;
+    let to = parse_version(CONTRACT_VERSION)?;
+    let applied = run_migrations(deps.branch(), &env, &msg, &from, &to, MIGRATIONS)?;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("from_version", stored.version)
+        .add_attribute("to_version", CONTRACT_VERSION)
+        .add_attribute("migrations", applied.join(",")))
+}
 
 /// Handling contract execution
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
     _env: Env,
-    _info: MessageInfo,
+    info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
@@ -87,11 +117,11 @@ pub fn execute(
             execution_state_root,
             proof_a,
             proof_b,
-            proof_c, } => execute::step(_env, deps, LightClientStep {
+            proof_c, } => execute::step(_env, deps, info, LightClientStep {
                 finalized_slot: Uint256::from(finalized_slot),
                 participation: Uint256::from(participation),
-                finalized_header_root: hex::decode(finalized_header_root).unwrap(),
-                execution_state_root: hex::decode(execution_state_root).unwrap(),
+                finalized_header_root: parse_root("finalized_header_root", &finalized_header_root)?,
+                execution_state_root: parse_root("execution_state_root", &execution_state_root)?,
                 proof: Groth16Proof {
                     a: proof_a.to_vec(),
                     b: vec![proof_b[0].to_vec(), proof_b[1].to_vec()],
@@ -109,26 +139,52 @@ pub fn execute(
             sync_committee_poseidon,
             rotate_proof_a,
             rotate_proof_b,
-            rotate_proof_c } => execute::rotate(deps, LightClientRotate { 
+            rotate_proof_c } => execute::rotate(_env, deps, info, LightClientRotate { 
                 step: LightClientStep {
                     finalized_slot: Uint256::from(finalized_slot),
                     participation: Uint256::from(participation),
-                    finalized_header_root: hex::decode(finalized_header_root).unwrap(),
-                    execution_state_root: hex::decode(execution_state_root).unwrap(),
+                    finalized_header_root: parse_root("finalized_header_root", &finalized_header_root)?,
+                    execution_state_root: parse_root("execution_state_root", &execution_state_root)?,
                     proof: Groth16Proof {
                         a: step_proof_a.to_vec(),
                         b: vec![step_proof_b[0].to_vec(), step_proof_b[1].to_vec()],
                         c: step_proof_c.to_vec(),
                     }
                 }, 
-                sync_committee_ssz: hex::decode(sync_committee_ssz).unwrap(), 
-                sync_committee_poseidon: Uint256::from_str(&sync_committee_poseidon).unwrap().to_le_bytes().to_vec(), 
+                sync_committee_ssz: parse_root("sync_committee_ssz", &sync_committee_ssz)?, 
+                sync_committee_poseidon: parse_poseidon("sync_committee_poseidon", &sync_committee_poseidon)?, 
                 proof: Groth16Proof {
                     a: rotate_proof_a.to_vec(),
                     b: vec![rotate_proof_b[0].to_vec(), rotate_proof_b[1].to_vec()],
                     c: rotate_proof_c.to_vec(),
                 } }),
-        ExecuteMsg::Force { period } => execute::force(_env, deps, Uint256::from(period)),
+        ExecuteMsg::Force { period } => execute::force(_env, deps, info, Uint256::from(period)),
+        ExecuteMsg::Recover { period,
+            sync_committee_poseidon,
+            head_slot,
+            header_root,
+            execution_state_root,
+            prune } => execute::recover(deps, info, Uint256::from(period), Uint256::from(head_slot),
+                parse_poseidon("sync_committee_poseidon", &sync_committee_poseidon)?,
+                parse_root("header_root", &header_root)?,
+                parse_root("execution_state_root", &execution_state_root)?,
+                prune.unwrap_or(false)),
+        ExecuteMsg::SetPaused { paused } => execute::set_paused(deps, info, paused),
+        ExecuteMsg::ProposeOwner { new_owner } => execute::propose_owner(deps, info, new_owner),
+        ExecuteMsg::AcceptOwner {} => execute::accept_owner(deps, info),
+        ExecuteMsg::RenounceOwner {} => execute::renounce_owner(deps, info),
+        ExecuteMsg::SetRelayerAllowlist { enabled } => execute::set_relayer_allowlist(deps, info, enabled),
+        ExecuteMsg::AddRelayer { relayer } => execute::add_relayer(deps, info, relayer),
+        ExecuteMsg::RemoveRelayer { relayer } => execute::remove_relayer(deps, info, relayer),
+        ExecuteMsg::SetRewards { denom, step_reward, rotate_reward } => execute::set_rewards(deps, info, denom, step_reward, rotate_reward),
+        ExecuteMsg::FundRewards {} => execute::fund_rewards(deps, info),
+        ExecuteMsg::SetMaxHeadLag { max_head_lag } => execute::set_max_head_lag(deps, info, max_head_lag),
+        ExecuteMsg::SetMaxFutureSlots { max_future_slots } => execute::set_max_future_slots(deps, info, max_future_slots),
+        ExecuteMsg::SetCapellaForkSlot { capella_fork_slot } => execute::set_capella_fork_slot(deps, info, capella_fork_slot),
+        ExecuteMsg::ProveHistoricalHeader { slot, target_slot, target_root, branch } => execute::prove_historical_header(deps, info, slot, target_slot,
+            parse_root("target_root", &target_root)?,
+            parse_branch("branch", &branch)?),
+        ExecuteMsg::UpdateVerifyingKey { circuit, vk } => execute::update_verifying_key(_env, deps, info, circuit, vk),
     }
 }
 
@@ -141,29 +197,50 @@ pub mod execute {
      *   2) A valid finality proof
      *   3) A valid execution state root proof
      */
-    pub fn step(_env: Env, mut deps: DepsMut, update: LightClientStep) -> Result<Response, ContractError>{
+    pub fn step(_env: Env, mut deps: DepsMut, info: MessageInfo, update: LightClientStep) -> Result<Response, ContractError>{
+        ensure_not_paused(deps.as_ref())?;
+        ensure_not_frozen(deps.as_ref())?;
+        ensure_relayer(deps.as_ref(), &info.sender)?;
+
+        let state = STATE.load(deps.storage)?;
+        if let Some(max_head_lag) = state.max_head_lag {
+            if update.finalized_slot + max_head_lag < state.head {
+                return Err(ContractError::UpdateTooOld { slot: update.finalized_slot.to_string(), head: state.head.to_string() });
+            }
+        }
+
         let finalized = process_step(deps.as_ref(), &update);
         if finalized.is_err() {
             return Err(finalized.err().unwrap());
         }
 
-        let current_slot = current_slot(_env, deps.as_ref())?;
-        if current_slot < update.finalized_slot {
+        let current_slot = current_slot(&_env, deps.as_ref())?;
+        if current_slot + state.max_future_slots < update.finalized_slot {
            return Err(ContractError::UpdateSlotTooFar {}); 
         }
 
-        let _res = set_head(deps.branch(), update.finalized_slot, update.finalized_header_root);
-        if _res.is_err() {
-            return Err(_res.err().unwrap())
-        }
+        let advances_head = update.finalized_slot > state.head;
+        let response = Response::new()
+            .add_attribute("action", "step")
+            .add_attribute("slot", update.finalized_slot)
+            .add_attribute("participation", update.participation);
 
-        let _res = set_execution_state_root(deps.branch(), update.finalized_slot, update.execution_state_root);
-        if _res.is_err() {
-            return Err(_res.err().unwrap())
+        // A conflicting header freezes the client, so the execution state root is not stored
+        let head_update = set_head(deps.branch(), &info.sender, update.finalized_slot, update.finalized_header_root)?;
+        if head_update.is_frozen() {
+            return Ok(response.add_event(head_update.into_event()));
         }
-        
-        // TODO: Add more specifics on response
-        Ok(Response::new().add_attribute("action", "step"))
+        let execution_state_root_update = set_execution_state_root(deps.branch(), &info.sender, update.finalized_slot, update.execution_state_root)?;
+        let frozen = execution_state_root_update.is_frozen();
+
+        let response = response
+            .add_event(head_update.into_event())
+            .add_event(execution_state_root_update.into_event());
+        // A step that froze the light client is not rewarded
+        if advances_head && !frozen {
+            return pay_reward(deps.as_ref(), &_env, response, info.sender, |config| config.step_reward);
+        }
+        Ok(response)
     }
     /*
      * @dev Sets the sync committee validator set root for the next sync
@@ -171,7 +248,10 @@ pub mod execute {
      * the case there is no finalization, we will keep track of the best
      * optimistic update.
      */
-    pub fn rotate(deps: DepsMut, update: LightClientRotate) -> Result<Response, ContractError>{
+    pub fn rotate(_env: Env, mut deps: DepsMut, info: MessageInfo, update: LightClientRotate) -> Result<Response, ContractError>{
+        ensure_not_paused(deps.as_ref())?;
+        ensure_not_frozen(deps.as_ref())?;
+        ensure_relayer(deps.as_ref(), &info.sender)?;
 
         let step = &update.step;
         let finalized = process_step(deps.as_ref(), step)?;
@@ -180,78 +260,356 @@ pub mod execute {
 
         let next_period = current_period + Uint256::from(1u64);
 
-        let result = zk_light_client_rotate(&update);
+        let result = zk_light_client_rotate(deps.as_ref(), &update);
         if result.is_err() {
             return Err(result.err().unwrap());
         }
 
+        let response = Response::new()
+            .add_attribute("action", "rotate")
+            .add_attribute("slot", step.finalized_slot)
+            .add_attribute("participation", step.participation)
+            .add_attribute("finalized", finalized.to_string());
+
         if finalized {
-            let _res = set_sync_committee_poseidon(deps, next_period, update.sync_committee_poseidon);
-            if _res.is_err() {
-                return Err(_res.err().unwrap())
+            let new_period = !SYNC_COMMITTEE_POSEIDONS.has(deps.storage, to_key(next_period)?);
+            let event = set_sync_committee_poseidon(deps.branch(), &info.sender, next_period, update.sync_committee_poseidon)?.into_event();
+            let response = response.add_event(event);
+            if new_period {
+                return pay_reward(deps.as_ref(), &_env, response, info.sender, |config| config.rotate_reward);
             }
+            Ok(response)
         } else {
-            // TODO: load is if definitely there, if not there, must do may load
-            let best_update = match BEST_UPDATES.may_load(deps.storage, current_period.to_string())?{
-                Some(update) => update,
-                None => return Err(ContractError::BestUpdateNotInitialized {}),
-            };
-
-            if step.participation < best_update.step.participation {
-                return Err(ContractError::ExistsBetterUpdate {});
+            // The first optimistic update of a period is always the best one so far
+            if let Some(best_update) = BEST_UPDATES.may_load(deps.storage, to_key(current_period)?)? {
+                if step.participation < best_update.step.participation {
+                    return Err(ContractError::ExistsBetterUpdate {});
+                }
             }
-            set_best_update(deps, current_period, update);
+            set_best_update(deps, current_period, update)?;
+            Ok(response.add_attribute("best_update_period", current_period))
         }
-
-        // TODO: Add more specifics on response
-        Ok(Response::new().add_attribute("action", "rotate"))
     }
     /*
     * @dev In the case that there is no finalization for a sync committee
     * rotation, applies the update with the most signatures throughout the
-    * period. 
-    * @todo: Add more details on how this works
+    * period. Anyone may force once the period has ended without the next sync
+    * committee being set by a finalized rotate.
     */
-    pub fn force(_env: Env, deps: DepsMut, period: Uint256) -> Result<Response, ContractError>{
-        // TODO: Check if deps.as_ref() is correct
-        let update = BEST_UPDATES.load(deps.storage, period.to_string())?;
-        let next_period = period + Uint256::from(1u64);
+    pub fn force(_env: Env, deps: DepsMut, info: MessageInfo, period: Uint256) -> Result<Response, ContractError>{
+        ensure_not_paused(deps.as_ref())?;
+        ensure_not_frozen(deps.as_ref())?;
 
-        let _next_sync_committee_poseidon = match SYNC_COMMITTEE_POSEIDONS.may_load(deps.storage, next_period.to_string())?{
-            Some(poseidon) => poseidon,
-            None => return Err(ContractError::SyncCommitteeAlreadyInitialized {}),
+        let update = match BEST_UPDATES.may_load(deps.storage, to_key(period)?)? {
+            Some(update) => update,
+            None => return Err(ContractError::BestUpdateNotInitialized {}),
         };
-        let slot = current_slot(_env, deps.as_ref())?;
+        let next_period = period + Uint256::from(1u64);
 
-        if update.step.finalized_header_root == vec![0; 32] {
-            return Err(ContractError::BestUpdateNotInitialized {});
-        } else if sync_committee_period(slot, deps.as_ref())? < next_period {
+        if SYNC_COMMITTEE_POSEIDONS.has(deps.storage, to_key(next_period)?) {
+            return Err(ContractError::SyncCommitteeAlreadyInitialized {});
+        } else if current_slot(&_env, deps.as_ref())? < first_slot_of_period(next_period, deps.as_ref())? {
             return Err(ContractError::CurrentSyncCommitteeNotEnded {});
         }
 
-        let _res = set_sync_committee_poseidon(deps, next_period, update.sync_committee_poseidon);
-        if _res.is_err() {
-            return Err(_res.err().unwrap());
+        let event = set_sync_committee_poseidon(deps, &info.sender, next_period, update.sync_committee_poseidon)?.into_event();
+
+        Ok(Response::new()
+            .add_attribute("action", "force")
+            .add_attribute("period", period)
+            .add_event(event))
+    }
+    /*
+    * @dev Governance escape hatch after a fork. Clears the inconsistency evidence and trusts the
+    * given sync committee and head outright, overwriting whatever was stored for them. With
+    * `prune`, roots verified after the anchor are removed since they may come from the fork;
+    * moving the head back is only allowed together with it, so no root is served past the head.
+    * Only callable by the owner of a frozen light client.
+    */
+    #[allow(clippy::too_many_arguments)]
+    pub fn recover(mut deps: DepsMut, info: MessageInfo, period: Uint256, head_slot: Uint256, sync_committee_poseidon: Vec<u8>,
+        header_root: Vec<u8>, execution_state_root: Vec<u8>, prune: bool) -> Result<Response, ContractError>{
+        let mut state = ensure_owner(deps.as_ref(), &info.sender)?;
+        if state.consistent {
+            return Err(ContractError::LightClientNotFrozen {});
+        }
+        if head_slot < state.head && !prune {
+            return Err(ContractError::RecoverWithoutPrune { head_slot: head_slot.to_string(), head: state.head.to_string() });
+        }
+
+        let pruned = if prune {
+            prune_after(deps.branch(), to_key(period)?, to_key(head_slot)?)?
+        } else {
+            0
+        };
+
+        SYNC_COMMITTEE_POSEIDONS.save(deps.storage, to_key(period)?, &sync_committee_poseidon)?;
+        HEADERS.save(deps.storage, to_key(head_slot)?, &header_root)?;
+        EXECUTION_STATE_ROOTS.save(deps.storage, to_key(head_slot)?, &execution_state_root)?;
+        INCONSISTENCY_EVIDENCE.remove(deps.storage);
+
+        state.consistent = true;
+        state.head = head_slot;
+        state.head_execution_state_root = execution_state_root.clone();
+        STATE.save(deps.storage, &state)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "recover")
+            .add_event(events::light_client_recovered(period, head_slot, pruned))
+            .add_event(events::sync_committee_update(period, poseidon_from_le_bytes(&sync_committee_poseidon)?))
+            .add_event(events::head_update(head_slot, &header_root))
+            .add_event(events::execution_state_root_update(head_slot, &execution_state_root)))
+    }
+    /*
+    * @dev Proves the block root of `target_slot` against the verified header of `slot` and
+    * stores it in HISTORICAL_BLOCK_ROOTS, so messages can be settled against blocks that never
+    * were a step target. A root conflicting with the one proven before for `target_slot` freezes
+    * the light client like a step would.
+    */
+    pub fn prove_historical_header(deps: DepsMut, info: MessageInfo, slot: u64, target_slot: u64, target_root: Vec<u8>, branch: Vec<[u8; 32]>) -> Result<Response, ContractError>{
+        ensure_not_paused(deps.as_ref())?;
+        ensure_not_frozen(deps.as_ref())?;
+        ensure_relayer(deps.as_ref(), &info.sender)?;
+
+        let header_root = vec_to_bytes("header_root", &HEADERS.load(deps.storage, slot)?)?;
+        let index = historical_header_index(deps.as_ref(), slot, target_slot)?;
+        if !is_valid_merkle_branch(&vec_to_bytes("target_root", &target_root)?, index, &branch, &header_root) {
+            return Err(ContractError::InvalidMerkleBranch { field: "target_root".to_string() });
         }
 
-        // TODO: Add more specifics on response
-        Ok(Response::new().add_attribute("action", "force"))
+        let update = match HISTORICAL_BLOCK_ROOTS.may_load(deps.storage, target_slot)? {
+            Some(existing_root) if existing_root != target_root => freeze(deps, InconsistencyEvidence {
+                kind: InconsistencyKind::HistoricalBlockRoot,
+                key: Uint256::from(target_slot),
+                existing_root: hex::encode(existing_root),
+                new_root: hex::encode(target_root),
+                submitter: info.sender,
+            })?,
+            _ => {
+                HISTORICAL_BLOCK_ROOTS.save(deps.storage, target_slot, &target_root)?;
+                RootUpdate::Stored(events::historical_header_proven(Uint256::from(target_slot), &target_root, Uint256::from(slot)))
+            }
+        };
+
+        Ok(Response::new()
+            .add_attribute("action", "prove_historical_header")
+            .add_attribute("slot", slot.to_string())
+            .add_attribute("target_slot", target_slot.to_string())
+            .add_event(update.into_event()))
+    }
+    /*
+    * @dev Halts or resumes light client updates. Only callable by the owner.
+    */
+    pub fn set_paused(deps: DepsMut, info: MessageInfo, paused: bool) -> Result<Response, ContractError>{
+        ensure_owner(deps.as_ref(), &info.sender)?;
+
+        PAUSED.save(deps.storage, &paused)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "set_paused")
+            .add_attribute("paused", paused.to_string()))
+    }
+    /*
+    * @dev Turns the relayer allowlist on or off. Only callable by the owner; the allowlist
+    * itself is kept either way.
+    */
+    pub fn set_relayer_allowlist(deps: DepsMut, info: MessageInfo, enabled: bool) -> Result<Response, ContractError>{
+        ensure_owner(deps.as_ref(), &info.sender)?;
+
+        RELAYER_ALLOWLIST_ENABLED.save(deps.storage, &enabled)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "set_relayer_allowlist")
+            .add_attribute("enabled", enabled.to_string()))
+    }
+    /*
+    * @dev Allows a relayer to submit updates while the allowlist is enabled. Only callable by the owner.
+    */
+    pub fn add_relayer(deps: DepsMut, info: MessageInfo, relayer: String) -> Result<Response, ContractError>{
+        ensure_owner(deps.as_ref(), &info.sender)?;
+        let relayer = deps.api.addr_validate(&relayer)?;
+
+        RELAYERS.save(deps.storage, &relayer, &Empty {})?;
+
+        Ok(Response::new()
+            .add_attribute("action", "add_relayer")
+            .add_attribute("relayer", relayer))
+    }
+    /*
+    * @dev Removes a relayer from the allowlist. Only callable by the owner.
+    */
+    pub fn remove_relayer(deps: DepsMut, info: MessageInfo, relayer: String) -> Result<Response, ContractError>{
+        ensure_owner(deps.as_ref(), &info.sender)?;
+        let relayer = deps.api.addr_validate(&relayer)?;
+
+        RELAYERS.remove(deps.storage, &relayer);
+
+        Ok(Response::new()
+            .add_attribute("action", "remove_relayer")
+            .add_attribute("relayer", relayer))
+    }
+    /*
+    * @dev Sets how far behind the head a step may finalize. Only callable by the owner.
+    */
+    pub fn set_max_head_lag(deps: DepsMut, info: MessageInfo, max_head_lag: Option<u32>) -> Result<Response, ContractError>{
+        let mut state = ensure_owner(deps.as_ref(), &info.sender)?;
+
+        state.max_head_lag = max_head_lag.map(Uint256::from);
+        STATE.save(deps.storage, &state)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "set_max_head_lag")
+            .add_attribute("max_head_lag", max_head_lag.map_or("none".to_string(), |lag| lag.to_string())))
+    }
+    /*
+    * @dev Sets how many slots ahead of the current slot a step may finalize. Only callable by the owner.
+    */
+    pub fn set_max_future_slots(deps: DepsMut, info: MessageInfo, max_future_slots: u32) -> Result<Response, ContractError>{
+        let mut state = ensure_owner(deps.as_ref(), &info.sender)?;
+
+        state.max_future_slots = Uint256::from(max_future_slots);
+        STATE.save(deps.storage, &state)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "set_max_future_slots")
+            .add_attribute("max_future_slots", max_future_slots.to_string()))
+    }
+    /*
+    * @dev Sets the first Capella slot historical headers are proven against. Only callable by the owner.
+    */
+    pub fn set_capella_fork_slot(deps: DepsMut, info: MessageInfo, capella_fork_slot: Option<u32>) -> Result<Response, ContractError>{
+        let mut state = ensure_owner(deps.as_ref(), &info.sender)?;
+
+        state.capella_fork_slot = capella_fork_slot.map(Uint256::from);
+        STATE.save(deps.storage, &state)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "set_capella_fork_slot")
+            .add_attribute("capella_fork_slot", capella_fork_slot.map_or("none".to_string(), |slot| slot.to_string())))
+    }
+    /*
+    * @dev Configures relayer rewards. Only callable by the owner.
+    */
+    pub fn set_rewards(deps: DepsMut, info: MessageInfo, denom: String, step_reward: Uint128, rotate_reward: Uint128) -> Result<Response, ContractError>{
+        ensure_owner(deps.as_ref(), &info.sender)?;
+
+        REWARD_CONFIG.save(deps.storage, &RewardConfig { denom: denom.clone(), step_reward, rotate_reward })?;
+
+        Ok(Response::new()
+            .add_attribute("action", "set_rewards")
+            .add_attribute("denom", denom)
+            .add_attribute("step_reward", step_reward)
+            .add_attribute("rotate_reward", rotate_reward))
+    }
+    /*
+    * @dev Adds the attached coins to the reward pool. The pool is the contract balance of the
+    * reward denom, so anyone may fund it but only with that denom.
+    */
+    pub fn fund_rewards(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError>{
+        let config = REWARD_CONFIG.may_load(deps.storage)?.ok_or(ContractError::RewardsNotConfigured {})?;
+        if info.funds.is_empty() || info.funds.iter().any(|coin| coin.denom != config.denom) {
+            return Err(ContractError::InvalidRewardFunds { denom: config.denom });
+        }
+        let amount: Uint128 = info.funds.iter().map(|coin| coin.amount).sum();
+
+        Ok(Response::new()
+            .add_attribute("action", "fund_rewards")
+            .add_attribute("funder", info.sender)
+            .add_attribute("amount", amount))
+    }
+    /*
+    * @dev Replaces the verifying key of a circuit. Only callable by the owner; the
+    * previous keys remain queryable through the key history.
+    */
+    pub fn update_verifying_key(_env: Env, deps: DepsMut, info: MessageInfo, circuit: Circuit, vk: VerifyingKeyJson) -> Result<Response, ContractError>{
+        ensure_owner(deps.as_ref(), &info.sender)?;
+
+        let version = set_verifying_key(deps, _env.block.height, &circuit, vk)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "update_verifying_key")
+            .add_attribute("circuit", circuit.as_str())
+            .add_attribute("version", version.to_string()))
+    }
+    /*
+    * @dev First step of an ownership transfer. The current owner stays in charge until the
+    * proposed owner accepts, so a mistyped address cannot lock the contract.
+    */
+    pub fn propose_owner(deps: DepsMut, info: MessageInfo, new_owner: String) -> Result<Response, ContractError>{
+        let mut state = ensure_owner(deps.as_ref(), &info.sender)?;
+        let new_owner = deps.api.addr_validate(&new_owner)?;
+
+        state.pending_owner = Some(new_owner.clone());
+        STATE.save(deps.storage, &state)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "propose_owner")
+            .add_attribute("pending_owner", new_owner))
+    }
+    /*
+    * @dev Second step of an ownership transfer, sent by the proposed owner.
+    */
+    pub fn accept_owner(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError>{
+        let mut state = STATE.load(deps.storage)?;
+        match &state.pending_owner {
+            Some(pending) if *pending == info.sender => {},
+            Some(_) => return Err(ContractError::Unauthorized {}),
+            None => return Err(ContractError::NoPendingOwner {}),
+        }
+
+        state.owner = state.pending_owner.take();
+        STATE.save(deps.storage, &state)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "accept_owner")
+            .add_attribute("owner", info.sender))
+    }
+    /*
+    * @dev Gives up ownership for good, together with any pending transfer. Keys can no longer
+    * be updated, the client can no longer be paused or recovered.
+    */
+    pub fn renounce_owner(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError>{
+        let mut state = ensure_owner(deps.as_ref(), &info.sender)?;
+
+        state.owner = None;
+        state.pending_owner = None;
+        STATE.save(deps.storage, &state)?;
+
+        Ok(Response::new().add_attribute("action", "renounce_owner"))
     }
-    
-    
 }
 
 /// Handling contract query
 #[cfg_attr(not(feature = "library"), entry_point)]
+#[allow(deprecated)]
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::GetSyncCommitteePeriod { slot } => to_binary(&query::get_sync_committee_period(slot, deps)?),
         QueryMsg::GetCurrentSlot {} => to_binary(&query::get_current_slot(_env, deps)?),
+        QueryMsg::VerifyingKeyHistory { circuit } => to_binary(&query::get_verifying_key_history(circuit, deps)?),
+        QueryMsg::Header { slot } => to_binary(&query::get_header(slot, deps)?),
+        QueryMsg::BlockRoot { slot } => to_binary(&query::get_block_root(slot, deps)?),
+        QueryMsg::ExecutionStateRoot { slot } => to_binary(&query::get_execution_state_root(slot, deps)?),
+        QueryMsg::SyncCommitteePoseidon { period } => to_binary(&query::get_sync_committee_poseidon(period, deps)?),
+        QueryMsg::BestUpdate { period } => to_binary(&query::get_best_update(period, deps)?),
+        QueryMsg::State {} => to_binary(&query::get_state(deps)?),
+        QueryMsg::ListHeaders { start_after, limit, order } => to_binary(&query::list_headers(start_after, limit, order, deps)?),
+        QueryMsg::ListExecutionStateRoots { start_after, limit, order } => to_binary(&query::list_execution_state_roots(start_after, limit, order, deps)?),
+        QueryMsg::InconsistencyEvidence {} => to_binary(&query::get_inconsistency_evidence(deps)?),
+        QueryMsg::ListRelayers { start_after, limit } => to_binary(&query::list_relayers(start_after, limit, deps)?),
+        QueryMsg::Rewards {} => to_binary(&query::get_rewards(_env, deps)?),
+        QueryMsg::ForceEligibility { period } => to_binary(&query::get_force_eligibility(_env, period, deps)?),
+        QueryMsg::Owner {} => to_binary(&query::get_owner(deps)?),
     }
 }
 
 pub mod query {
-    use crate::msg::{GetSyncCommitteePeriodResponse, GetCurrentSlotResponse};
+    use crate::msg::{GetSyncCommitteePeriodResponse, GetCurrentSlotResponse, VerifyingKeyHistoryResponse, VerifyingKeyVersionResponse,
+        HeaderResponse, ExecutionStateRootResponse, SyncCommitteePoseidonResponse, BestUpdateResponse, StateResponse,
+        ListHeadersResponse, ListExecutionStateRootsResponse, OrderBy, InconsistencyEvidenceResponse, OwnerResponse, ListRelayersResponse, RewardsResponse, ForceEligibilityResponse};
+
+    const DEFAULT_LIMIT: u32 = 30;
+    const MAX_LIMIT: u32 = 100;
 
     use super::*;
 
@@ -261,9 +619,177 @@ pub mod query {
     }
 
     pub fn get_current_slot(_env: Env, deps: Deps) -> StdResult<GetCurrentSlotResponse> {
-        let slot = current_slot(_env, deps)?;
+        let slot = current_slot(&_env, deps).map_err(|err| StdError::generic_err(err.to_string()))?;
         Ok(GetCurrentSlotResponse { slot })
     }
+
+    pub fn get_verifying_key_history(circuit: Circuit, deps: Deps) -> StdResult<VerifyingKeyHistoryResponse> {
+        let (_, history) = verifying_key_storage(&circuit);
+        let versions = history
+            .range(deps.storage, None, None, Order::Ascending)
+            .map(|item| {
+                let (version, entry) = item?;
+                Ok(VerifyingKeyVersionResponse {
+                    version,
+                    activated_at_height: entry.activated_at_height,
+                    vk: entry.vk,
+                })
+            })
+            .collect::<StdResult<Vec<_>>>()?;
+        Ok(VerifyingKeyHistoryResponse { versions })
+    }
+
+    pub fn get_header(slot: u64, deps: Deps) -> StdResult<HeaderResponse> {
+        ensure_serving(deps)?;
+        let root = HEADERS.load(deps.storage, slot)?;
+        Ok(HeaderResponse { slot, root: hex::encode(root) })
+    }
+
+    pub fn get_block_root(slot: u64, deps: Deps) -> StdResult<HeaderResponse> {
+        ensure_serving(deps)?;
+        let root = match HEADERS.may_load(deps.storage, slot)? {
+            Some(root) => root,
+            None => HISTORICAL_BLOCK_ROOTS.load(deps.storage, slot)?,
+        };
+        Ok(HeaderResponse { slot, root: hex::encode(root) })
+    }
+
+    pub fn get_execution_state_root(slot: u64, deps: Deps) -> StdResult<ExecutionStateRootResponse> {
+        ensure_serving(deps)?;
+        let root = EXECUTION_STATE_ROOTS.load(deps.storage, slot)?;
+        Ok(ExecutionStateRootResponse { slot, root: hex::encode(root) })
+    }
+
+    pub fn get_sync_committee_poseidon(period: u64, deps: Deps) -> StdResult<SyncCommitteePoseidonResponse> {
+        ensure_serving(deps)?;
+        let poseidon = SYNC_COMMITTEE_POSEIDONS.load(deps.storage, period)?;
+        Ok(SyncCommitteePoseidonResponse { period, poseidon: poseidon_from_le_bytes(&poseidon)? })
+    }
+
+    pub fn get_best_update(period: u64, deps: Deps) -> StdResult<BestUpdateResponse> {
+        ensure_serving(deps)?;
+        let update = BEST_UPDATES.load(deps.storage, period)?;
+        Ok(BestUpdateResponse {
+            period,
+            finalized_slot: update.step.finalized_slot,
+            participation: update.step.participation,
+            finalized_header_root: hex::encode(update.step.finalized_header_root),
+            execution_state_root: hex::encode(update.step.execution_state_root),
+            sync_committee_ssz: hex::encode(update.sync_committee_ssz),
+            sync_committee_poseidon: poseidon_from_le_bytes(&update.sync_committee_poseidon)?,
+        })
+    }
+
+    pub fn get_state(deps: Deps) -> StdResult<StateResponse> {
+        ensure_serving(deps)?;
+        let state = STATE.load(deps.storage)?;
+        Ok(StateResponse {
+            consistent: state.consistent,
+            head: state.head,
+            head_execution_state_root: hex::encode(state.head_execution_state_root),
+            max_head_lag: state.max_head_lag,
+            max_future_slots: state.max_future_slots,
+            capella_fork_slot: state.capella_fork_slot,
+            genesis_validators_root: hex::encode(state.genesis_validators_root),
+            genesis_time: state.genesis_time,
+            seconds_per_slot: state.seconds_per_slot,
+            slots_per_period: state.slots_per_period,
+            owner: state.owner,
+            paused: PAUSED.may_load(deps.storage)?.unwrap_or(false),
+        })
+    }
+
+    pub fn list_headers(start_after: Option<u64>, limit: Option<u32>, order: Option<OrderBy>, deps: Deps) -> StdResult<ListHeadersResponse> {
+        ensure_serving(deps)?;
+        let headers = list_roots(HEADERS, start_after, limit, order, deps)?
+            .into_iter()
+            .map(|(slot, root)| HeaderResponse { slot, root: hex::encode(root) })
+            .collect();
+        Ok(ListHeadersResponse { headers })
+    }
+
+    pub fn list_execution_state_roots(start_after: Option<u64>, limit: Option<u32>, order: Option<OrderBy>, deps: Deps) -> StdResult<ListExecutionStateRootsResponse> {
+        ensure_serving(deps)?;
+        let roots = list_roots(EXECUTION_STATE_ROOTS, start_after, limit, order, deps)?
+            .into_iter()
+            .map(|(slot, root)| ExecutionStateRootResponse { slot, root: hex::encode(root) })
+            .collect();
+        Ok(ListExecutionStateRootsResponse { roots })
+    }
+
+    pub fn list_relayers(start_after: Option<String>, limit: Option<u32>, deps: Deps) -> StdResult<ListRelayersResponse> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start_after = start_after.map(|addr| deps.api.addr_validate(&addr)).transpose()?;
+        let relayers = RELAYERS
+            .keys(deps.storage, start_after.as_ref().map(Bound::exclusive), None, Order::Ascending)
+            .take(limit)
+            .collect::<StdResult<Vec<_>>>()?;
+        Ok(ListRelayersResponse {
+            enabled: RELAYER_ALLOWLIST_ENABLED.may_load(deps.storage)?.unwrap_or(false),
+            relayers,
+        })
+    }
+
+    pub fn get_rewards(_env: Env, deps: Deps) -> StdResult<RewardsResponse> {
+        let config = REWARD_CONFIG.may_load(deps.storage)?;
+        let pool = match &config {
+            Some(config) => Some(deps.querier.query_balance(&_env.contract.address, &config.denom)?),
+            None => None,
+        };
+        Ok(RewardsResponse { config, pool })
+    }
+
+    pub fn get_force_eligibility(_env: Env, period: u64, deps: Deps) -> StdResult<ForceEligibilityResponse> {
+        let best_update = BEST_UPDATES.may_load(deps.storage, period)?;
+        let next_period = Uint256::from(period) + Uint256::from(1u64);
+        let next_sync_committee_initialized = SYNC_COMMITTEE_POSEIDONS.has(deps.storage, to_key(next_period)?);
+        let earliest_force_slot = first_slot_of_period(next_period, deps)?;
+        // Mirrors the checks in `execute::force`, short of pause and freeze
+        let eligible = best_update.is_some()
+            && !next_sync_committee_initialized
+            && current_slot(&_env, deps).is_ok_and(|slot| slot >= earliest_force_slot);
+
+        Ok(ForceEligibilityResponse {
+            period,
+            best_update_exists: best_update.is_some(),
+            participation: best_update.map(|update| update.step.participation),
+            next_sync_committee_initialized,
+            earliest_force_slot,
+            eligible,
+        })
+    }
+
+    pub fn get_owner(deps: Deps) -> StdResult<OwnerResponse> {
+        let state = STATE.load(deps.storage)?;
+        Ok(OwnerResponse { owner: state.owner, pending_owner: state.pending_owner })
+    }
+
+    pub fn get_inconsistency_evidence(deps: Deps) -> StdResult<InconsistencyEvidenceResponse> {
+        let evidence = INCONSISTENCY_EVIDENCE.may_load(deps.storage)?.unwrap_or_default();
+        Ok(InconsistencyEvidenceResponse { evidence })
+    }
+
+    /*
+    * @dev Verified roots are not served once the light client is frozen, consumers must not
+    * act on a history the sync committee has contradicted.
+    */
+    fn ensure_serving(deps: Deps) -> StdResult<()> {
+        ensure_not_frozen(deps).map_err(|err| StdError::generic_err(err.to_string()))
+    }
+
+    /*
+    * @dev Pages through a slot keyed map, starting after `start_after` in the requested order.
+    */
+    fn list_roots(map: Map<u64, Vec<u8>>, start_after: Option<u64>, limit: Option<u32>, order: Option<OrderBy>, deps: Deps) -> StdResult<Vec<(u64, Vec<u8>)>> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let order: Order = order.unwrap_or(OrderBy::Ascending).into();
+        let start = start_after.map(Bound::exclusive);
+        let (min, max) = match order {
+            Order::Ascending => (start, None),
+            Order::Descending => (None, start),
+        };
+        map.range(deps.storage, min, max, order).take(limit).collect()
+    }
 }
 
 /// Handling submessage reply.
@@ -282,78 +808,186 @@ pub fn reply(_deps: DepsMut, _env: Env, _msg: Reply) -> Result<Response, Contrac
 * @dev Sets the sync committee validator set root for the next sync
 * committee period. If the root is already set and the new root does not
 * match, the contract is marked as inconsistent. Otherwise, we store the
-* root and emit an event.
+* root. Returns the event describing the outcome.
 */
-fn set_sync_committee_poseidon(deps: DepsMut, period: Uint256, poseidon: Vec<u8>) -> Result<(), ContractError> {
-    let mut state = STATE.load(deps.storage)?;
-
-    let poseidon_for_period = match SYNC_COMMITTEE_POSEIDONS.may_load(deps.storage, period.to_string())?{
+fn set_sync_committee_poseidon(deps: DepsMut, submitter: &Addr, period: Uint256, poseidon: Vec<u8>) -> Result<RootUpdate, ContractError> {
+    let poseidon_for_period = match SYNC_COMMITTEE_POSEIDONS.may_load(deps.storage, to_key(period)?)?{
         Some(poseidon) => poseidon,
         None => vec![0; 32],
     };   
     if poseidon_for_period != [0; 32] && poseidon_for_period != poseidon {
-        state.consistent = false;
-        return Ok(())
+        return freeze(deps, InconsistencyEvidence {
+            kind: InconsistencyKind::SyncCommittee,
+            key: period,
+            existing_root: poseidon_from_le_bytes(&poseidon_for_period)?.to_string(),
+            new_root: poseidon_from_le_bytes(&poseidon)?.to_string(),
+            submitter: submitter.clone(),
+        });
     }
-    SYNC_COMMITTEE_POSEIDONS.save(deps.storage, period.to_string(), &poseidon)?;
+    SYNC_COMMITTEE_POSEIDONS.save(deps.storage, to_key(period)?, &poseidon)?;
 
-    // TODO: Add emit event for SyncCommitteePoseidonUpdate
-    Ok(())
+    Ok(RootUpdate::Stored(events::sync_committee_update(period, poseidon_from_le_bytes(&poseidon)?)))
 
 }
 
     /*
-     * @dev Update the head of the client after checking for the existence of signatures and valid proofs.
+     * @dev Stores the header root of a verified slot and moves the head to it if it is newer.
      */
-fn set_head(deps: DepsMut, slot: Uint256, root: Vec<u8>) -> Result<(), ContractError> {
-    let mut state = STATE.load(deps.storage)?;
-
-    let root_for_slot = match HEADERS.may_load(deps.storage, slot.to_string())?{
+fn set_head(deps: DepsMut, submitter: &Addr, slot: Uint256, root: Vec<u8>) -> Result<RootUpdate, ContractError> {
+    let root_for_slot = match HEADERS.may_load(deps.storage, to_key(slot)?)?{
         Some(root) => root,
         None => vec![0; 32],
     };
     // If sync committee does not exist    
     if root_for_slot != vec![0; 32] && root_for_slot != root {
-        state.consistent = false;
-        return Ok(())
+        return freeze(deps, InconsistencyEvidence {
+            kind: InconsistencyKind::Header,
+            key: slot,
+            existing_root: hex::encode(root_for_slot),
+            new_root: hex::encode(root),
+            submitter: submitter.clone(),
+        });
     }
 
-    state.head = slot;
-
-    HEADERS.save(deps.storage, slot.to_string(), &root)?;
+    HEADERS.save(deps.storage, to_key(slot)?, &root)?;
+    STATE.update(deps.storage, |mut state| -> StdResult<_> {
+        if slot > state.head {
+            state.head = slot;
+        }
+        Ok(state)
+    })?;
 
-    // TODO: Add emit event for HeadUpdate
-    Ok(())
+    Ok(RootUpdate::Stored(events::head_update(slot, &root)))
 }
 
     /*
      * @dev Update execution root as long as it is consistent with the current head or 
      * it is the execution root for the slot.
      */
-fn set_execution_state_root(deps: DepsMut, slot: Uint256, root: Vec<u8>) -> Result<(), ContractError> {
-    let mut state = STATE.load(deps.storage)?;
-
-    let root_for_slot = match EXECUTION_STATE_ROOTS.may_load(deps.storage, slot.to_string())?{
+fn set_execution_state_root(deps: DepsMut, submitter: &Addr, slot: Uint256, root: Vec<u8>) -> Result<RootUpdate, ContractError> {
+    let root_for_slot = match EXECUTION_STATE_ROOTS.may_load(deps.storage, to_key(slot)?)?{
         Some(root) => root,
         None => vec![0; 32],
     };
     // If sync committee does not exist    
     if root_for_slot != vec![0; 32] && root_for_slot != root {
+        return freeze(deps, InconsistencyEvidence {
+            kind: InconsistencyKind::ExecutionStateRoot,
+            key: slot,
+            existing_root: hex::encode(root_for_slot),
+            new_root: hex::encode(root),
+            submitter: submitter.clone(),
+        });
+    }
+
+    EXECUTION_STATE_ROOTS.save(deps.storage, to_key(slot)?, &root)?;
+    STATE.update(deps.storage, |mut state| -> StdResult<_> {
+        if slot == state.head {
+            state.head_execution_state_root = root.clone();
+        }
+        Ok(state)
+    })?;
+    Ok(RootUpdate::Stored(events::execution_state_root_update(slot, &root)))
+}
+
+/*
+* @dev Outcome of storing a verified root, with the event to emit either way. A root that
+* conflicts with the stored one is not stored but freezes the light client.
+*/
+enum RootUpdate {
+    Stored(Event),
+    Frozen(Event),
+}
+
+impl RootUpdate {
+    fn is_frozen(&self) -> bool {
+        matches!(self, RootUpdate::Frozen(_))
+    }
+
+    fn into_event(self) -> Event {
+        match self {
+            RootUpdate::Stored(event) | RootUpdate::Frozen(event) => event,
+        }
+    }
+}
+
+/*
+* @dev Marks the light client as inconsistent and records the conflicting roots. Conflicting
+* finalized roots mean the sync committee signed two histories, so every update is refused
+* from here on. The caller must still return `Ok` so the frozen state is committed.
+*/
+fn freeze(deps: DepsMut, evidence: InconsistencyEvidence) -> Result<RootUpdate, ContractError> {
+    STATE.update(deps.storage, |mut state| -> StdResult<_> {
         state.consistent = false;
-        return Ok(())
+        Ok(state)
+    })?;
+    let event = events::inconsistency_detected(&evidence);
+    let mut recorded = INCONSISTENCY_EVIDENCE.may_load(deps.storage)?.unwrap_or_default();
+    recorded.push(evidence);
+    INCONSISTENCY_EVIDENCE.save(deps.storage, &recorded)?;
+    Ok(RootUpdate::Frozen(event))
+}
+
+/*
+* @dev Removes headers and execution state roots after `slot`, sync committees after `period`
+* and best updates from `period` on. Historical block roots are all removed, since the headers
+* they were proven against may be gone. Returns the number of removed entries.
+*/
+fn prune_after(deps: DepsMut, period: u64, slot: u64) -> StdResult<usize> {
+    let mut pruned = 0;
+    for (map, after) in [(HEADERS, slot), (EXECUTION_STATE_ROOTS, slot), (SYNC_COMMITTEE_POSEIDONS, period)] {
+        let keys = map
+            .keys(deps.storage, Some(Bound::exclusive(after)), None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+        for key in &keys {
+            map.remove(deps.storage, *key);
+        }
+        pruned += keys.len();
     }
 
-    EXECUTION_STATE_ROOTS.save(deps.storage, slot.to_string(), &root)?;
-    Ok(())
+    let keys = BEST_UPDATES
+        .keys(deps.storage, Some(Bound::inclusive(period)), None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for key in &keys {
+        BEST_UPDATES.remove(deps.storage, *key);
+    }
+    pruned += keys.len();
+
+    let keys = HISTORICAL_BLOCK_ROOTS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for key in &keys {
+        HISTORICAL_BLOCK_ROOTS.remove(deps.storage, *key);
+    }
+    Ok(pruned + keys.len())
+}
+
+/*
+* @dev Activates a new verifying key for the circuit and appends it to the key history
+* together with the block height it became active at. Returns the new key version.
+*/
+pub(crate) fn set_verifying_key(deps: DepsMut, height: u64, circuit: &Circuit, vk: VerifyingKeyJson) -> Result<u32, ContractError> {
+    validate_verifying_key(circuit, &vk)?;
+
+    let (active, history) = verifying_key_storage(circuit);
+    let version = match history.keys(deps.storage, None, None, Order::Descending).next() {
+        Some(last) => last? + 1,
+        None => 1,
+    };
+    // Prepare the key once here so proofs can be verified without re-deriving it
+    let prepared = Verifier::new(vk.clone())?.to_bytes()?;
+    history.save(deps.storage, version, &VerifyingKeyVersion { vk, activated_at_height: height })?;
+    deps.storage.set(active, &prepared);
+
+    Ok(version)
 }
 
     /*
      * @dev Save the best update for the period.
      */
-fn set_best_update(deps: DepsMut, period: Uint256, update: LightClientRotate) {
-    let period_str = period.to_string();
-    // TODO: Confirm save is the correct usage
-    let _res = BEST_UPDATES.save(deps.storage, period_str, &update);
+fn set_best_update(deps: DepsMut, period: Uint256, update: LightClientRotate) -> Result<(), ContractError> {
+    BEST_UPDATES.save(deps.storage, to_key(period)?, &update)?;
+    Ok(())
 }
 
 
@@ -362,15 +996,49 @@ fn sync_committee_period(slot: Uint256, deps: Deps) -> StdResult<Uint256> {
     Ok(slot / state.slots_per_period)
 }
 
-fn current_slot(_env: Env, deps: Deps) -> StdResult<Uint256> {
+/*
+* @dev First slot of a sync committee period, the earliest slot the previous period can be forced at.
+*/
+fn first_slot_of_period(period: Uint256, deps: Deps) -> StdResult<Uint256> {
     let state = STATE.load(deps.storage)?;
-    let block = _env.block;
-    let timestamp = Uint256::from(block.time.seconds());
-    // TODO: Confirm this is timestamp in CosmWasm
-    let current_slot = timestamp + state.genesis_time / state.seconds_per_slot;
-    Ok(current_slot)
+    Ok(period.checked_mul(state.slots_per_period)?)
 }
 
+fn current_slot(env: &Env, deps: Deps) -> Result<Uint256, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    let timestamp = Uint256::from(env.block.time.seconds());
+    // There is no slot before genesis, fail rather than wrap
+    let since_genesis = timestamp.checked_sub(state.genesis_time).map_err(|_| ContractError::BeforeGenesis {
+        time: env.block.time.seconds(),
+        genesis_time: state.genesis_time.to_string(),
+    })?;
+    Ok(since_genesis.checked_div(state.seconds_per_slot).map_err(StdError::from)?)
+}
+
+
+
+/*
+* @dev Generalized index of the block root of `target_slot` relative to the header of `slot`.
+* `state.block_roots` holds the SLOTS_PER_HISTORICAL_ROOT slots before `slot`, older slots are
+* reached through the historical summary of their era, appended at every era end since Capella.
+*/
+fn historical_header_index(deps: Deps, slot: u64, target_slot: u64) -> Result<u64, ContractError> {
+    if target_slot >= slot {
+        return Err(ContractError::InvalidHistoricalSlot { slot, target_slot });
+    }
+    let index = if slot - target_slot <= SLOTS_PER_HISTORICAL_ROOT {
+        block_roots_index(target_slot)
+    } else {
+        let capella_fork_slot = STATE.load(deps.storage)?.capella_fork_slot.ok_or(ContractError::CapellaForkSlotNotSet {})?;
+        let first_era = to_key(capella_fork_slot)? / SLOTS_PER_HISTORICAL_ROOT;
+        let era = target_slot / SLOTS_PER_HISTORICAL_ROOT;
+        if era < first_era {
+            return Err(ContractError::InvalidHistoricalSlot { slot, target_slot });
+        }
+        historical_block_root_index(era - first_era, target_slot)
+    };
+    Ok(concat_gindex(HEADER_STATE_ROOT_INDEX, index))
+}
 
 
 /* CORE LOGIC FUNCTIONS */
@@ -383,7 +1051,7 @@ fn process_step(deps: Deps, update: &LightClientStep) -> Result<bool, ContractEr
     let current_period = sync_committee_period(update.finalized_slot, deps)?;
 
     // Load poseidon for period
-    let _sync_committee_poseidon = match SYNC_COMMITTEE_POSEIDONS.may_load(deps.storage, current_period.to_string())? {
+    let _sync_committee_poseidon = match SYNC_COMMITTEE_POSEIDONS.may_load(deps.storage, to_key(current_period)?)? {
         Some(poseidon) => Some(poseidon),
         None => return Err(ContractError::SyncCommitteeNotInitialized {  }),
     };
@@ -409,47 +1077,19 @@ fn process_step(deps: Deps, update: &LightClientStep) -> Result<bool, ContractEr
 * initialize a verifier, and verify the proof.
 */
 fn zk_light_client_step(deps: Deps, update: &LightClientStep) -> Result<(), ContractError> {
-    // Set up initial bytes
-    let finalized_slot_le = update.finalized_slot.to_le_bytes();
-    let participation_le = update.participation.to_le_bytes();
     let current_period = sync_committee_period(update.finalized_slot, deps)?;
-    let sync_committee_poseidon = SYNC_COMMITTEE_POSEIDONS.load(deps.storage, current_period.to_string())?;
-
-
-    let mut h = [0u8; 32];
-    let mut temp = [0u8; 64];
-    // sha256 & combine inputs
-    temp[..32].copy_from_slice(&finalized_slot_le);
-    temp[32..].copy_from_slice(&update.finalized_header_root);
-    h.copy_from_slice(&Sha256::digest(temp));
+    let sync_committee_poseidon = SYNC_COMMITTEE_POSEIDONS.load(deps.storage, to_key(current_period)?)?;
 
-    temp[..32].copy_from_slice(&h);
-    temp[32..].copy_from_slice(&participation_le);
-    h.copy_from_slice(&Sha256::digest(temp));
-
-    temp[..32].copy_from_slice(&h);
-    temp[32..].copy_from_slice(&update.execution_state_root);
-    h.copy_from_slice(&Sha256::digest(temp));
-
-    temp[..32].copy_from_slice(&h);
-    temp[32..].copy_from_slice(&sync_committee_poseidon);
-    h.copy_from_slice(&Sha256::digest(temp));
-
-    // TODO: Confirm this is the correct math!
-
-    let mut t = [255u8; 32];
-    t[31] = 0b00011111;
-
-    for i in 0..32 {
-        t[i] &= h[i];
-    }
-
-    // Set proof
-    let inputs_string = Uint256::from_le_bytes(t).to_string();
-    let inputs = vec![inputs_string; 1];
+    let commitment = step_commitment(
+        update.finalized_slot,
+        &vec_to_bytes("finalized_header_root", &update.finalized_header_root)?,
+        update.participation,
+        &vec_to_bytes("execution_state_root", &update.execution_state_root)?,
+        &vec_to_bytes("sync_committee_poseidon", &sync_committee_poseidon)?,
+    );
 
     // Init verifier
-    let verifier = Verifier::new_step_verifier();
+    let verifier = load_verifier(deps, &Circuit::Step)?;
 
     // TODO: Remove Groth16Proof struct?
     let groth_16_proof = update.proof.clone();
@@ -462,10 +1102,9 @@ fn zk_light_client_step(deps: Deps, update: &LightClientStep) -> Result<(), Cont
         curve: "bn128".to_string(),
     };
     
-    let proof = circom_proof.to_proof();
-    let public_signals = PublicSignals::from(inputs);
+    let proof = circom_proof.to_proof()?;
 
-    let result = verifier.verify_proof(proof, &public_signals.get());
+    let result = verifier.verify_proof(proof, &[commitment])?;
     if !result {
         return Err(ContractError::InvalidStepProof { });
     }
@@ -479,26 +1118,26 @@ fn zk_light_client_step(deps: Deps, update: &LightClientStep) -> Result<(), Cont
 * the finalized_header_root big endian bytes and the sync committee's poseidon hash. Initialize a verifier, 
 * and verify the proof.
 */
-fn zk_light_client_rotate(update: &LightClientRotate) -> Result<(), ContractError> {
+fn zk_light_client_rotate(deps: Deps, update: &LightClientRotate) -> Result<(), ContractError> {
 
     let mut inputs = vec!["0".to_string(); 65];
 
     // Set up inputs correctly
-    let sync_committee_ssz_numeric = Uint256::from_be_bytes(vec_to_bytes(&update.sync_committee_ssz));
+    let sync_committee_ssz_numeric = Uint256::from_be_bytes(vec_to_bytes("sync_committee_ssz", &update.sync_committee_ssz)?);
     let sync_committee_ssz_numeric_be = sync_committee_ssz_numeric.to_be_bytes();
     for i in 0..32 {
         inputs[i] = sync_committee_ssz_numeric_be[i].to_string();
     }
 
-    let finalized_header_root_numeric = Uint256::from_be_bytes(vec_to_bytes(&update.step.finalized_header_root));
+    let finalized_header_root_numeric = Uint256::from_be_bytes(vec_to_bytes("finalized_header_root", &update.step.finalized_header_root)?);
     let finalized_header_root_numeric_be = finalized_header_root_numeric.to_be_bytes();
     for i in 0..32 {
         inputs[32+i] = finalized_header_root_numeric_be[i].to_string();
     }
 
-    inputs[64] = Uint256::from_le_bytes(vec_to_bytes(&update.sync_committee_poseidon)).to_string();
+    inputs[64] = Uint256::from_le_bytes(vec_to_bytes("sync_committee_poseidon", &update.sync_committee_poseidon)?).to_string();
 
-    let verifier = Verifier::new_rotate_verifier();
+    let verifier = load_verifier(deps, &Circuit::Rotate)?;
 
     let groth_16_proof = update.proof.clone();
 
@@ -510,11 +1149,11 @@ fn zk_light_client_rotate(update: &LightClientRotate) -> Result<(), ContractErro
         curve: "bn128".to_string(),
     };
 
-    let proof = circom_proof.to_proof();
+    let proof = circom_proof.to_proof()?;
 
     let public_signals = PublicSignals::from(inputs);
 
-    let result = verifier.verify_proof(proof, &public_signals.get());
+    let result = verifier.verify_proof(proof, &public_signals.get()?)?;
 
     if !result {
         return Err(ContractError::InvalidRotateProof { });
@@ -525,24 +1164,228 @@ fn zk_light_client_rotate(update: &LightClientRotate) -> Result<(), ContractErro
 
 /* HELPER FUNCTIONS */
 
+/*
+* @dev Storage for the active verifying key and the key history of a circuit.
+*/
+fn verifying_key_storage(circuit: &Circuit) -> (&'static [u8], Map<'static, u32, VerifyingKeyVersion>) {
+    match circuit {
+        Circuit::Step => (STEP_PREPARED_VERIFYING_KEY, STEP_VERIFYING_KEY_HISTORY),
+        Circuit::Rotate => (ROTATE_PREPARED_VERIFYING_KEY, ROTATE_VERIFYING_KEY_HISTORY),
+    }
+}
+
+/*
+* @dev Loads the verifier for a circuit from its stored prepared verifying key.
+*/
+fn load_verifier(deps: Deps, circuit: &Circuit) -> StdResult<Verifier> {
+    let (active, _) = verifying_key_storage(circuit);
+    let bytes = deps.storage.get(active).ok_or_else(|| StdError::not_found("PreparedVerifyingKey"))?;
+    Verifier::from_bytes(&bytes)
+}
+
+/*
+* @dev Sanity checks a snarkjs verifying key against the shape of the circuit it is meant for.
+*/
+fn validate_verifying_key(circuit: &Circuit, vk: &VerifyingKeyJson) -> Result<(), ContractError> {
+    let expected_inputs = match circuit {
+        Circuit::Step => STEP_PUBLIC_INPUTS,
+        Circuit::Rotate => ROTATE_PUBLIC_INPUTS,
+    };
+
+    let reason = if vk.protocol != "groth16" || vk.curve != "bn128" {
+        format!("expected a groth16 key over bn128, got {} over {}", vk.protocol, vk.curve)
+    } else if vk.inputs_count != expected_inputs {
+        format!("expected {} public inputs, got {}", expected_inputs, vk.inputs_count)
+    } else if vk.ic.len() != expected_inputs as usize + 1 {
+        format!("expected {} IC points, got {}", expected_inputs + 1, vk.ic.len())
+    } else {
+        return Ok(());
+    };
+
+    Err(ContractError::InvalidVerifyingKey { circuit: circuit.as_str().to_string(), reason })
+}
+
+/*
+* @dev Loads the state, failing unless `sender` is the owner.
+*/
+fn ensure_owner(deps: Deps, sender: &Addr) -> Result<State, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    if state.owner.as_ref() != Some(sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    Ok(state)
+}
+
+/*
+* @dev Pays the relayer of a useful update out of the reward pool. An empty pool never fails
+* the update itself, the relayer just goes unpaid.
+*/
+fn pay_reward(deps: Deps, env: &Env, response: Response, relayer: Addr, amount: fn(&RewardConfig) -> Uint128) -> Result<Response, ContractError> {
+    let config = match REWARD_CONFIG.may_load(deps.storage)? {
+        Some(config) => config,
+        None => return Ok(response),
+    };
+    let amount = amount(&config);
+    if amount.is_zero() {
+        return Ok(response);
+    }
+
+    let pool = deps.querier.query_balance(&env.contract.address, &config.denom)?;
+    if pool.amount < amount {
+        return Ok(response.add_attribute("reward", "0"));
+    }
+
+    Ok(response
+        .add_attribute("reward", amount)
+        .add_message(BankMsg::Send {
+            to_address: relayer.into_string(),
+            amount: vec![Coin { denom: config.denom, amount }],
+        }))
+}
+
+/*
+* @dev Fails if the relayer allowlist is enabled and does not contain `sender`.
+*/
+fn ensure_relayer(deps: Deps, sender: &Addr) -> Result<(), ContractError> {
+    if RELAYER_ALLOWLIST_ENABLED.may_load(deps.storage)?.unwrap_or(false) && !RELAYERS.has(deps.storage, sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    Ok(())
+}
+
+/*
+* @dev Fails while the owner has paused updates.
+*/
+fn ensure_not_paused(deps: Deps) -> Result<(), ContractError> {
+    if PAUSED.may_load(deps.storage)?.unwrap_or(false) {
+        return Err(ContractError::Paused {});
+    }
+    Ok(())
+}
+
+/*
+* @dev Fails once the light client has been frozen by an inconsistency.
+*/
+fn ensure_not_frozen(deps: Deps) -> Result<(), ContractError> {
+    if !STATE.load(deps.storage)?.consistent {
+        return Err(ContractError::LightClientFrozen {});
+    }
+    Ok(())
+}
+
+/*
+* @dev Storage key for a slot or period. Values beyond u64 are unreachable on any beacon chain.
+*/
+pub(crate) fn to_key(value: Uint256) -> StdResult<u64> {
+    let bytes = value.to_be_bytes();
+    if bytes[..24].iter().any(|b| *b != 0) {
+        return Err(ConversionOverflowError::new("Uint256", "u64", value.to_string()).into());
+    }
+    let mut key = [0u8; 8];
+    key.copy_from_slice(&bytes[24..]);
+    Ok(u64::from_be_bytes(key))
+}
+
 /*
 * @dev Converts a vector to bytes.
 */
-fn vec_to_bytes(vec: &[u8]) -> [u8; 32] {
-    let mut bytes = [0u8; 32];
-    bytes.copy_from_slice(vec);
-    bytes
+fn vec_to_bytes(field: &str, vec: &[u8]) -> Result<[u8; 32], ContractError> {
+    vec.try_into().map_err(|_| ContractError::InvalidRootLength {
+        field: field.to_string(),
+        expected: 32,
+        got: vec.len(),
+    })
+}
+
+/*
+* @dev Decodes a hex encoded 32 byte root.
+*/
+fn parse_root(field: &str, root: &str) -> Result<Vec<u8>, ContractError> {
+    let bytes = hex::decode(root).map_err(|_| ContractError::InvalidHex { field: field.to_string() })?;
+    vec_to_bytes(field, &bytes)?;
+    Ok(bytes)
 }
 
+/*
+* @dev Decodes a hex encoded Merkle branch.
+*/
+fn parse_branch(field: &str, branch: &[String]) -> Result<Vec<[u8; 32]>, ContractError> {
+    branch.iter().map(|node| vec_to_bytes(field, &parse_root(field, node)?)).collect()
+}
+
+/*
+* @dev Parses a decimal sync committee poseidon into its little endian byte representation.
+*/
+fn parse_poseidon(field: &str, poseidon: &str) -> Result<Vec<u8>, ContractError> {
+    let poseidon = Uint256::from_str(poseidon).map_err(|_| ContractError::InvalidFieldElement {
+        field: field.to_string(),
+        value: poseidon.to_string(),
+    })?;
+    Ok(poseidon.to_le_bytes().to_vec())
+}
+
+/*
+* @dev Reads a stored little endian sync committee poseidon back into its numeric value.
+*/
+fn poseidon_from_le_bytes(poseidon: &[u8]) -> StdResult<Uint256> {
+    let bytes: [u8; 32] = poseidon
+        .try_into()
+        .map_err(|_| StdError::invalid_data_size(32, poseidon.len()))?;
+    Ok(Uint256::from_le_bytes(bytes))
+}
 
 #[cfg(test)]
+#[allow(deprecated)]
 mod tests {
     use super::*;
     use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
     use cosmwasm_std::{coins};
-    use hex::{decode};
+    use crate::helpers::fixtures;
     use crate::state::{Groth16Proof};
-    use std::str::{FromStr, from_utf8};
+    use crate::msg::{VerifyingKeyHistoryResponse, HeaderResponse, ExecutionStateRootResponse, SyncCommitteePoseidonResponse, StateResponse,
+        ListHeadersResponse, ListExecutionStateRootsResponse, OrderBy, InconsistencyEvidenceResponse, OwnerResponse, ListRelayersResponse, RewardsResponse, GetCurrentSlotResponse, ForceEligibilityResponse};
+    use cosmwasm_std::{from_binary, Timestamp};
+
+    fn goerli_instantiate_msg() -> InstantiateMsg {
+        InstantiateMsg {
+            genesis_validators_root: "043db0d9a83813551ee2f33450d23797757d430911a9320530ad8a0eabc43efb".to_string(),
+            genesis_time: 1616508000,
+            seconds_per_slot: 12,
+            slots_per_period: 8192,
+            sync_committee_period: 532,
+            sync_committee_poseidon: "7032059424740925146199071046477651269705772793323287102921912953216115444414".to_string(),
+            step_verifying_key: fixtures::step_verifying_key(),
+            rotate_verifying_key: fixtures::rotate_verifying_key(),
+            owner: None,
+            max_head_lag: None,
+            max_future_slots: None,
+            capella_fork_slot: None,
+        }
+    }
+
+    // A block time shortly after the slots finalized by the Goerli fixtures
+    fn goerli_env() -> Env {
+        env_at(1616508000 + 4360032 * 12 + 30)
+    }
+
+    fn env_at(seconds: u64) -> Env {
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(seconds);
+        env
+    }
+
+    // The step from testStep() in LightClient.t.sol
+    fn goerli_step_msg() -> ExecuteMsg {
+        ExecuteMsg::Step {
+            finalized_slot: 4359840,
+            participation: 432,
+            finalized_header_root: "70d0a7f53a459dd88eb37c6cfdfb8c48f120e504c96b182357498f2691aa5653".to_string(),
+            execution_state_root: "69d746cb81cd1fb4c11f4dcc04b6114596859b518614da0dd3b4192ff66c3a58".to_string(),
+            proof_a: ["14717729948616455402271823418418032272798439132063966868750456734930753033999".to_string(), "10284862272179454279380723177303354589165265724768792869172425850641532396958".to_string()],
+            proof_b: [["11269943315518713067124801671029240901063146909738584854987772776806315890545".to_string(), "20094085308485991030092338753416508135313449543456147939097124612984047201335".to_string()], ["8122139689435793554974799663854817979475528090524378333920791336987132768041".to_string(), "5111528818556913201486596055325815760919897402988418362773344272232635103877".to_string()]],
+            proof_c: ["6410073677012431469384941862462268198904303371106734783574715889381934207004".to_string(), "11977981471972649035068934866969447415783144961145315609294880087827694234248".to_string()],
+        }
+    }
 
     #[test]
     fn proper_initialization() {
@@ -550,17 +1393,23 @@ mod tests {
 
         // TODO: Update default msg with values from Gnosis
         let msg = InstantiateMsg { 
-            genesis_validators_root: "0".to_string(),
+            genesis_validators_root: "043db0d9a83813551ee2f33450d23797757d430911a9320530ad8a0eabc43efb".to_string(),
             genesis_time: 0,
-            seconds_per_slot: 0,
-            slots_per_period: 0,
+            seconds_per_slot: 5,
+            slots_per_period: 8192,
             sync_committee_period: 0,
             sync_committee_poseidon: "0".to_string(), 
+            step_verifying_key: fixtures::step_verifying_key(),
+            rotate_verifying_key: fixtures::rotate_verifying_key(),
+            owner: None,
+            max_head_lag: None,
+            max_future_slots: None,
+            capella_fork_slot: None,
         };
         let info = mock_info("creator", &coins(1000, "earth"));
 
         // we can just call .unwrap() to assert this was a success
-        let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let res = instantiate(deps.as_mut(), goerli_env(), info, msg).unwrap();
         assert_eq!(0, res.messages.len());
 
     }
@@ -576,11 +1425,17 @@ mod tests {
             slots_per_period: 8192,
             sync_committee_period: 532,
             sync_committee_poseidon: "7032059424740925146199071046477651269705772793323287102921912953216115444414".to_string(),
+            step_verifying_key: fixtures::step_verifying_key(),
+            rotate_verifying_key: fixtures::rotate_verifying_key(),
+            owner: None,
+            max_head_lag: None,
+            max_future_slots: None,
+            capella_fork_slot: None,
         };
         let info = mock_info("creator", &coins(1000, "earth"));
 
         // we can just call .unwrap() to assert this was a success
-        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let _res = instantiate(deps.as_mut(), goerli_env(), info, msg).unwrap();
 
         // beneficiary can release it
         let info = mock_info("anyone", &coins(2, "token"));
@@ -600,15 +1455,15 @@ mod tests {
         let proof_a: [String; 2] = proof.a.try_into().unwrap();
         let proof_b: [[String; 2]; 2] = [proof.b[0].clone().try_into().unwrap(), proof.b[1].clone().try_into().unwrap()];
         let proof_c: [String; 2] = proof.c.try_into().unwrap();
-        let msg = ExecuteMsg::Step {finalized_slot: finalized_slot,
-            participation: participation,
-            finalized_header_root: finalized_header_root,
-            execution_state_root: execution_state_root,
-            proof_a: proof_a,
-            proof_b: proof_b,
-            proof_c: proof_c};
+        let msg = ExecuteMsg::Step {finalized_slot,
+            participation,
+            finalized_header_root,
+            execution_state_root,
+            proof_a,
+            proof_b,
+            proof_c};
         
-        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let _res = execute(deps.as_mut(), goerli_env(), info, msg).unwrap();
         // let value: Get = from_binary(&res).unwrap();
 
     }
@@ -625,11 +1480,17 @@ mod tests {
             slots_per_period: 8192,
             sync_committee_period: 532,
             sync_committee_poseidon: "7032059424740925146199071046477651269705772793323287102921912953216115444414".to_string(),
+            step_verifying_key: fixtures::step_verifying_key(),
+            rotate_verifying_key: fixtures::rotate_verifying_key(),
+            owner: None,
+            max_head_lag: None,
+            max_future_slots: None,
+            capella_fork_slot: None,
         };
         let info = mock_info("creator", &coins(1000, "earth"));
 
         // we can just call .unwrap() to assert this was a success
-        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let _res = instantiate(deps.as_mut(), goerli_env(), info, msg).unwrap();
 
         // beneficiary can release it
         let info = mock_info("anyone", &coins(2, "token"));
@@ -666,19 +1527,19 @@ mod tests {
         let rotate_proof_b: [[String; 2]; 2] = [ssz_proof.b[0].clone().try_into().unwrap(), ssz_proof.b[1].clone().try_into().unwrap()];
         let rotate_proof_c: [String; 2] = ssz_proof.c.try_into().unwrap();
 
-        let msg = ExecuteMsg::Rotate {finalized_slot: finalized_slot,
-            participation: participation,
-            finalized_header_root: finalized_header_root,
-            execution_state_root: execution_state_root,
-            step_proof_a: step_proof_a,
-            step_proof_b: step_proof_b,
-            step_proof_c: step_proof_c,
-            sync_committee_ssz: sync_committee_ssz,
-            sync_committee_poseidon: sync_committee_poseidon,
-            rotate_proof_a: rotate_proof_a,
-            rotate_proof_b: rotate_proof_b,
-            rotate_proof_c: rotate_proof_c,};
-        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let msg = ExecuteMsg::Rotate {finalized_slot,
+            participation,
+            finalized_header_root,
+            execution_state_root,
+            step_proof_a,
+            step_proof_b,
+            step_proof_c,
+            sync_committee_ssz,
+            sync_committee_poseidon,
+            rotate_proof_a,
+            rotate_proof_b,
+            rotate_proof_c,};
+        let _res = execute(deps.as_mut(), goerli_env(), info, msg).unwrap();
 
         // TODO: Perform query and confirm it completed a rotate
 
@@ -689,27 +1550,763 @@ mod tests {
         let mut deps = mock_dependencies();
 
         let msg = InstantiateMsg { 
-            genesis_validators_root: "0".to_string(),
+            genesis_validators_root: "043db0d9a83813551ee2f33450d23797757d430911a9320530ad8a0eabc43efb".to_string(),
             genesis_time: 1616508000,
             seconds_per_slot: 12,
             slots_per_period: 8192,
             sync_committee_period: 532,
             sync_committee_poseidon: "0".to_string(), 
+            step_verifying_key: fixtures::step_verifying_key(),
+            rotate_verifying_key: fixtures::rotate_verifying_key(),
+            owner: None,
+            max_head_lag: None,
+            max_future_slots: None,
+            capella_fork_slot: None,
         };
         let info = mock_info("creator", &coins(1000, "earth"));
 
         // we can just call .unwrap() to assert this was a success
-        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let _res = instantiate(deps.as_mut(), goerli_env(), info, msg).unwrap();
 
         // beneficiary can release it
         let info = mock_info("anyone", &coins(2, "token"));
 
         // let period = Uint256::from(0u64);
 
+        // No best update has been recorded for the period, so there is nothing to force
         let msg = ExecuteMsg::Force {period: 0};
-        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let err = execute(deps.as_mut(), goerli_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::BestUpdateNotInitialized {}));
 
-        // should complete a force operation
+    }
+
+    #[test]
+    fn update_verifying_key() {
+        let mut deps = mock_dependencies();
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), goerli_env(), info, goerli_instantiate_msg()).unwrap();
+
+        // Only the owner may rotate keys
+        let msg = ExecuteMsg::UpdateVerifyingKey { circuit: Circuit::Step, vk: fixtures::step_verifying_key() };
+        let err = execute(deps.as_mut(), goerli_env(), mock_info("anyone", &[]), msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
+        // Keys for the wrong circuit are rejected
+        let bad_msg = ExecuteMsg::UpdateVerifyingKey { circuit: Circuit::Step, vk: fixtures::rotate_verifying_key() };
+        let err = execute(deps.as_mut(), goerli_env(), mock_info("creator", &[]), bad_msg).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidVerifyingKey { .. }));
+
+        let mut env = goerli_env();
+        env.block.height += 100;
+        let res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(res.attributes[2].value, "2");
+
+        let res = query(deps.as_ref(), goerli_env(), QueryMsg::VerifyingKeyHistory { circuit: Circuit::Step }).unwrap();
+        let history: VerifyingKeyHistoryResponse = from_binary(&res).unwrap();
+        assert_eq!(history.versions.len(), 2);
+        assert_eq!(history.versions[0].version, 1);
+        assert_eq!(history.versions[0].activated_at_height, goerli_env().block.height);
+        assert_eq!(history.versions[1].version, 2);
+        assert_eq!(history.versions[1].activated_at_height, env.block.height);
+
+        // The rotate key history is untouched
+        let res = query(deps.as_ref(), goerli_env(), QueryMsg::VerifyingKeyHistory { circuit: Circuit::Rotate }).unwrap();
+        let history: VerifyingKeyHistoryResponse = from_binary(&res).unwrap();
+        assert_eq!(history.versions.len(), 1);
+    }
+
+    #[test]
+    fn malformed_step_input() {
+        let mut deps = mock_dependencies();
+        instantiate(deps.as_mut(), goerli_env(), mock_info("creator", &[]), goerli_instantiate_msg()).unwrap();
+
+        let step_msg = |finalized_header_root: &str, execution_state_root: &str| ExecuteMsg::Step {
+            finalized_slot: 4359840,
+            participation: 432,
+            finalized_header_root: finalized_header_root.to_string(),
+            execution_state_root: execution_state_root.to_string(),
+            proof_a: ["1".to_string(), "2".to_string()],
+            proof_b: [["0".to_string(), "0".to_string()], ["0".to_string(), "0".to_string()]],
+            proof_c: ["1".to_string(), "2".to_string()],
+        };
+        let root = "70d0a7f53a459dd88eb37c6cfdfb8c48f120e504c96b182357498f2691aa5653";
+
+        let err = execute(deps.as_mut(), goerli_env(), mock_info("anyone", &[]), step_msg("zz", root)).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidHex { field } if field == "finalized_header_root"));
+
+        let err = execute(deps.as_mut(), goerli_env(), mock_info("anyone", &[]), step_msg(root, "70d0")).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidRootLength { expected: 32, got: 2, .. }));
+
+        // pi_b = (0, 0) is not a point on the twist
+        let err = execute(deps.as_mut(), goerli_env(), mock_info("anyone", &[]), step_msg(root, root)).unwrap_err();
+        assert!(matches!(err, ContractError::PointNotOnCurve { point } if point == "pi_b"));
+
+        for msg in [
+            InstantiateMsg { seconds_per_slot: 0, ..goerli_instantiate_msg() },
+            InstantiateMsg { slots_per_period: 0, ..goerli_instantiate_msg() },
+        ] {
+            let err = instantiate(deps.as_mut(), goerli_env(), mock_info("creator", &[]), msg).unwrap_err();
+            assert!(matches!(err, ContractError::ZeroValue { .. }));
+        }
+
+        let mut msg = goerli_instantiate_msg();
+        msg.sync_committee_poseidon = "not a number".to_string();
+        let err = instantiate(deps.as_mut(), goerli_env(), mock_info("creator", &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidFieldElement { field, .. } if field == "sync_committee_poseidon"));
+    }
+
+    #[test]
+    fn migrate_versions() {
+        let mut deps = mock_dependencies();
+        instantiate(deps.as_mut(), goerli_env(), mock_info("creator", &[]), goerli_instantiate_msg()).unwrap();
+
+        // Migrating to the version already stored runs no steps and leaves the state as it is
+        let res = migrate(deps.as_mut(), goerli_env(), MigrateMsg::default()).unwrap();
+        assert_eq!(res.attributes[3].value, "");
+        assert_eq!(get_contract_version(deps.as_ref().storage).unwrap().version, CONTRACT_VERSION);
+        let res = query(deps.as_ref(), goerli_env(), QueryMsg::Owner {}).unwrap();
+        assert_eq!(from_binary::<OwnerResponse>(&res).unwrap().owner, Some(Addr::unchecked("creator")));
+        execute(deps.as_mut(), goerli_env(), mock_info("anyone", &[]), goerli_step_msg()).unwrap();
+
+        // Downgrades are refused
+        set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "99.0.0").unwrap();
+        let err = migrate(deps.as_mut(), goerli_env(), MigrateMsg::default()).unwrap_err();
+        assert!(matches!(err, ContractError::CannotDowngrade { .. }));
+
+        // So is migrating state written by another contract
+        set_contract_version(deps.as_mut().storage, "crates.io:cw20-base", "0.0.1").unwrap();
+        let err = migrate(deps.as_mut(), goerli_env(), MigrateMsg::default()).unwrap_err();
+        assert!(matches!(err, ContractError::CannotMigrate { .. }));
+    }
+
+    #[test]
+    fn migrate_from_0_1_0() {
+        const LEGACY_HEADERS: Map<String, Vec<u8>> = Map::new("headers");
+        const LEGACY_EXECUTION_STATE_ROOTS: Map<String, Vec<u8>> = Map::new("execution_state_roots");
+        const LEGACY_SYNC_COMMITTEE_POSEIDONS: Map<String, Vec<u8>> = Map::new("sync_committee_poseidons");
+
+        // A 0.1.0 deployment: no owner, keys compiled in, maps keyed by decimal strings
+        let mut deps = mock_dependencies();
+        let genesis_validators_root = hex::decode("043db0d9a83813551ee2f33450d23797757d430911a9320530ad8a0eabc43efb").unwrap();
+        let legacy = format!(
+            r#"{{"consistent":true,"head":"4359000","genesis_validators_root":{:?},"genesis_time":"1616508000","seconds_per_slot":"12","slots_per_period":"8192"}}"#,
+            genesis_validators_root,
+        );
+        deps.as_mut().storage.set(b"state", legacy.as_bytes());
+        set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "0.1.0").unwrap();
+        LEGACY_HEADERS.save(deps.as_mut().storage, "4359000".to_string(), &vec![1; 32]).unwrap();
+        LEGACY_EXECUTION_STATE_ROOTS.save(deps.as_mut().storage, "4359000".to_string(), &vec![7; 32]).unwrap();
+        let poseidon = parse_poseidon("poseidon", &goerli_instantiate_msg().sync_committee_poseidon).unwrap();
+        LEGACY_SYNC_COMMITTEE_POSEIDONS.save(deps.as_mut().storage, "532".to_string(), &poseidon).unwrap();
+
+        // The keys and the owner can not be recovered from the old state
+        let err = migrate(deps.as_mut(), goerli_env(), MigrateMsg::default()).unwrap_err();
+        assert!(matches!(err, ContractError::MissingMigrateField { field } if field == "owner"));
+
+        let msg = MigrateMsg {
+            owner: Some("owner".to_string()),
+            step_verifying_key: Some(fixtures::step_verifying_key()),
+            rotate_verifying_key: Some(fixtures::rotate_verifying_key()),
+        };
+        let res = migrate(deps.as_mut(), goerli_env(), msg).unwrap();
+        assert_eq!(res.attributes[3].value, "0.2.0");
+
+        let res = query(deps.as_ref(), goerli_env(), QueryMsg::State {}).unwrap();
+        let state: StateResponse = from_binary(&res).unwrap();
+        assert_eq!(state.owner, Some(Addr::unchecked("owner")));
+        assert_eq!(state.head, Uint256::from(4359000u64));
+        assert_eq!(state.head_execution_state_root, hex::encode([7; 32]));
+        assert_eq!(state.max_head_lag, None);
+        assert_eq!(state.max_future_slots, Uint256::zero());
+        let res = query(deps.as_ref(), goerli_env(), QueryMsg::Header { slot: 4359000 }).unwrap();
+        assert_eq!(from_binary::<HeaderResponse>(&res).unwrap().root, hex::encode([1; 32]));
+        let res = query(deps.as_ref(), goerli_env(), QueryMsg::VerifyingKeyHistory { circuit: Circuit::Step }).unwrap();
+        assert_eq!(from_binary::<VerifyingKeyHistoryResponse>(&res).unwrap().versions.len(), 1);
+
+        // The migrated keys verify proofs and the owner can manage the contract
+        execute(deps.as_mut(), goerli_env(), mock_info("anyone", &[]), goerli_step_msg()).unwrap();
+        let res = query(deps.as_ref(), goerli_env(), QueryMsg::Header { slot: 4359840 }).unwrap();
+        assert_eq!(from_binary::<HeaderResponse>(&res).unwrap().root, "70d0a7f53a459dd88eb37c6cfdfb8c48f120e504c96b182357498f2691aa5653");
+        execute(deps.as_mut(), goerli_env(), mock_info("owner", &[]), ExecuteMsg::SetPaused { paused: true }).unwrap();
+    }
+
+    #[test]
+    fn query_light_client_data() {
+        let mut deps = mock_dependencies();
+        instantiate(deps.as_mut(), goerli_env(), mock_info("creator", &[]), goerli_instantiate_msg()).unwrap();
+
+        let res = query(deps.as_ref(), goerli_env(), QueryMsg::SyncCommitteePoseidon { period: 532 }).unwrap();
+        let poseidon: SyncCommitteePoseidonResponse = from_binary(&res).unwrap();
+        assert_eq!(poseidon.poseidon, Uint256::from_str("7032059424740925146199071046477651269705772793323287102921912953216115444414").unwrap());
+
+        let res = query(deps.as_ref(), goerli_env(), QueryMsg::State {}).unwrap();
+        let state: StateResponse = from_binary(&res).unwrap();
+        assert!(state.consistent);
+        assert_eq!(state.genesis_validators_root, "043db0d9a83813551ee2f33450d23797757d430911a9320530ad8a0eabc43efb");
+        assert_eq!(state.owner, Some(Addr::unchecked("creator")));
+
+        // Nothing has been verified yet
+        assert!(query(deps.as_ref(), goerli_env(), QueryMsg::Header { slot: 4359840 }).is_err());
+        assert!(query(deps.as_ref(), goerli_env(), QueryMsg::BestUpdate { period: 532 }).is_err());
+
+        execute(deps.as_mut(), goerli_env(), mock_info("anyone", &[]), goerli_step_msg()).unwrap();
+
+        let res = query(deps.as_ref(), goerli_env(), QueryMsg::Header { slot: 4359840 }).unwrap();
+        let header: HeaderResponse = from_binary(&res).unwrap();
+        assert_eq!(header.root, "70d0a7f53a459dd88eb37c6cfdfb8c48f120e504c96b182357498f2691aa5653");
+
+        let res = query(deps.as_ref(), goerli_env(), QueryMsg::ExecutionStateRoot { slot: 4359840 }).unwrap();
+        let root: ExecutionStateRootResponse = from_binary(&res).unwrap();
+        assert_eq!(root.root, "69d746cb81cd1fb4c11f4dcc04b6114596859b518614da0dd3b4192ff66c3a58");
+    }
+
+    #[test]
+    fn list_headers_and_execution_state_roots() {
+        let mut deps = mock_dependencies();
+        instantiate(deps.as_mut(), goerli_env(), mock_info("creator", &[]), goerli_instantiate_msg()).unwrap();
+        for slot in [9u64, 10, 100, 4359840] {
+            HEADERS.save(deps.as_mut().storage, slot, &vec![slot as u8; 32]).unwrap();
+            EXECUTION_STATE_ROOTS.save(deps.as_mut().storage, slot, &vec![!(slot as u8); 32]).unwrap();
+        }
+
+        let list_headers = |start_after, limit, order| {
+            let res = query(deps.as_ref(), goerli_env(), QueryMsg::ListHeaders { start_after, limit, order }).unwrap();
+            from_binary::<ListHeadersResponse>(&res).unwrap().headers.into_iter().map(|h| h.slot).collect::<Vec<u64>>()
+        };
+        assert_eq!(list_headers(None, None, None), vec![9, 10, 100, 4359840]);
+        assert_eq!(list_headers(Some(9), Some(2), None), vec![10, 100]);
+        assert_eq!(list_headers(None, Some(1), Some(OrderBy::Descending)), vec![4359840]);
+        assert_eq!(list_headers(Some(100), None, Some(OrderBy::Descending)), vec![10, 9]);
+        assert_eq!(list_headers(Some(4359840), None, None), Vec::<u64>::new());
+
+        let res = query(deps.as_ref(), goerli_env(), QueryMsg::ListExecutionStateRoots { start_after: Some(10), limit: Some(1), order: None }).unwrap();
+        let roots: ListExecutionStateRootsResponse = from_binary(&res).unwrap();
+        assert_eq!(roots.roots, vec![ExecutionStateRootResponse { slot: 100, root: hex::encode([!100u8; 32]) }]);
+    }
+
+    #[test]
+    fn step_emits_events() {
+        let mut deps = mock_dependencies();
+        let res = instantiate(deps.as_mut(), goerli_env(), mock_info("creator", &[]), goerli_instantiate_msg()).unwrap();
+        assert_eq!(res.events, vec![Event::new("sync_committee_update")
+            .add_attribute("period", "532")
+            .add_attribute("root", "7032059424740925146199071046477651269705772793323287102921912953216115444414")]);
+
+        let res = execute(deps.as_mut(), goerli_env(), mock_info("anyone", &[]), goerli_step_msg()).unwrap();
+        assert_eq!(res.events, vec![
+            Event::new("head_update")
+                .add_attribute("slot", "4359840")
+                .add_attribute("root", "70d0a7f53a459dd88eb37c6cfdfb8c48f120e504c96b182357498f2691aa5653"),
+            Event::new("execution_state_root_update")
+                .add_attribute("slot", "4359840")
+                .add_attribute("root", "69d746cb81cd1fb4c11f4dcc04b6114596859b518614da0dd3b4192ff66c3a58"),
+        ]);
+
+        // A conflicting header for an already verified slot is reported instead of stored
+        HEADERS.save(deps.as_mut().storage, 4359840, &vec![1; 32]).unwrap();
+        let res = execute(deps.as_mut(), goerli_env(), mock_info("anyone", &[]), goerli_step_msg()).unwrap();
+        assert_eq!(res.events, vec![Event::new("inconsistency_detected")
+            .add_attribute("kind", "header")
+            .add_attribute("slot", "4359840")
+            .add_attribute("existing_root", hex::encode([1; 32]))
+            .add_attribute("new_root", "70d0a7f53a459dd88eb37c6cfdfb8c48f120e504c96b182357498f2691aa5653")
+            .add_attribute("submitter", "anyone")]);
+    }
+
+    #[test]
+    fn inconsistency_freezes_light_client() {
+        let mut deps = mock_dependencies();
+        instantiate(deps.as_mut(), goerli_env(), mock_info("creator", &[]), goerli_instantiate_msg()).unwrap();
+        execute(deps.as_mut(), goerli_env(), mock_info("anyone", &[]), goerli_step_msg()).unwrap();
+
+        let update = LightClientRotate {
+            step: LightClientStep {
+                finalized_slot: Uint256::from(4360032u64),
+                participation: Uint256::from(300u64),
+                finalized_header_root: vec![1; 32],
+                execution_state_root: vec![2; 32],
+                proof: Groth16Proof { a: vec![], b: vec![], c: vec![] },
+            },
+            sync_committee_ssz: vec![3; 32],
+            sync_committee_poseidon: Uint256::from(42u64).to_le_bytes().to_vec(),
+            proof: Groth16Proof { a: vec![], b: vec![], c: vec![] },
+        };
+        BEST_UPDATES.save(deps.as_mut().storage, 532, &update).unwrap();
+        query(deps.as_ref(), goerli_env(), QueryMsg::BestUpdate { period: 532 }).unwrap();
+
+        // Another valid proof for the same slot, but the stored execution state root differs
+        EXECUTION_STATE_ROOTS.save(deps.as_mut().storage, 4359840, &vec![2; 32]).unwrap();
+        execute(deps.as_mut(), goerli_env(), mock_info("relayer", &[]), goerli_step_msg()).unwrap();
+        assert!(!STATE.load(deps.as_ref().storage).unwrap().consistent);
+
+        let res = query(deps.as_ref(), goerli_env(), QueryMsg::InconsistencyEvidence {}).unwrap();
+        let evidence: InconsistencyEvidenceResponse = from_binary(&res).unwrap();
+        assert_eq!(evidence.evidence, vec![InconsistencyEvidence {
+            kind: InconsistencyKind::ExecutionStateRoot,
+            key: Uint256::from(4359840u64),
+            existing_root: hex::encode([2; 32]),
+            new_root: "69d746cb81cd1fb4c11f4dcc04b6114596859b518614da0dd3b4192ff66c3a58".to_string(),
+            submitter: Addr::unchecked("relayer"),
+        }]);
+
+        // Updates are refused and verified roots are no longer served
+        let err = execute(deps.as_mut(), goerli_env(), mock_info("anyone", &[]), goerli_step_msg()).unwrap_err();
+        assert!(matches!(err, ContractError::LightClientFrozen {}));
+        let err = execute(deps.as_mut(), goerli_env(), mock_info("anyone", &[]), ExecuteMsg::Force { period: 532 }).unwrap_err();
+        assert!(matches!(err, ContractError::LightClientFrozen {}));
+        assert!(query(deps.as_ref(), goerli_env(), QueryMsg::Header { slot: 4359840 }).is_err());
+        assert!(query(deps.as_ref(), goerli_env(), QueryMsg::ExecutionStateRoot { slot: 4359840 }).is_err());
+        assert!(query(deps.as_ref(), goerli_env(), QueryMsg::SyncCommitteePoseidon { period: 532 }).is_err());
+        assert!(query(deps.as_ref(), goerli_env(), QueryMsg::ListHeaders { start_after: None, limit: None, order: None }).is_err());
+        assert!(query(deps.as_ref(), goerli_env(), QueryMsg::BestUpdate { period: 532 }).is_err());
+        assert!(query(deps.as_ref(), goerli_env(), QueryMsg::State {}).is_err());
+    }
+
+    #[test]
+    fn recover_unfreezes_light_client() {
+        let mut deps = mock_dependencies();
+        instantiate(deps.as_mut(), goerli_env(), mock_info("creator", &[]), goerli_instantiate_msg()).unwrap();
+        execute(deps.as_mut(), goerli_env(), mock_info("anyone", &[]), goerli_step_msg()).unwrap();
+        HEADERS.save(deps.as_mut().storage, 4359841, &vec![3; 32]).unwrap();
+        SYNC_COMMITTEE_POSEIDONS.save(deps.as_mut().storage, 533, &vec![3; 32]).unwrap();
+        HEADERS.save(deps.as_mut().storage, 4359840, &vec![1; 32]).unwrap();
+        execute(deps.as_mut(), goerli_env(), mock_info("anyone", &[]), goerli_step_msg()).unwrap();
+
+        let recover_msg = || ExecuteMsg::Recover {
+            period: 532,
+            sync_committee_poseidon: "7032059424740925146199071046477651269705772793323287102921912953216115444414".to_string(),
+            head_slot: 4359840,
+            header_root: "70d0a7f53a459dd88eb37c6cfdfb8c48f120e504c96b182357498f2691aa5653".to_string(),
+            execution_state_root: "69d746cb81cd1fb4c11f4dcc04b6114596859b518614da0dd3b4192ff66c3a58".to_string(),
+            prune: Some(true),
+        };
+        let err = execute(deps.as_mut(), goerli_env(), mock_info("anyone", &[]), recover_msg()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
+        // The head only moves back if everything verified after it is dropped
+        let mut behind_head = recover_msg();
+        if let ExecuteMsg::Recover { head_slot, prune, .. } = &mut behind_head {
+            *head_slot = 4359839;
+            *prune = None;
+        }
+        let err = execute(deps.as_mut(), goerli_env(), mock_info("creator", &[]), behind_head).unwrap_err();
+        assert!(matches!(err, ContractError::RecoverWithoutPrune { .. }));
+
+        let res = execute(deps.as_mut(), goerli_env(), mock_info("creator", &[]), recover_msg()).unwrap();
+        assert_eq!(res.events[0], Event::new("light_client_recovered")
+            .add_attribute("period", "532")
+            .add_attribute("head_slot", "4359840")
+            .add_attribute("pruned", "2"));
+
+        let res = query(deps.as_ref(), goerli_env(), QueryMsg::State {}).unwrap();
+        let state: StateResponse = from_binary(&res).unwrap();
+        assert!(state.consistent);
+        assert_eq!(state.head, Uint256::from(4359840u64));
+        let res = query(deps.as_ref(), goerli_env(), QueryMsg::InconsistencyEvidence {}).unwrap();
+        assert!(from_binary::<InconsistencyEvidenceResponse>(&res).unwrap().evidence.is_empty());
+
+        // The anchor replaces the conflicting header and everything after it is gone
+        let res = query(deps.as_ref(), goerli_env(), QueryMsg::Header { slot: 4359840 }).unwrap();
+        assert_eq!(from_binary::<HeaderResponse>(&res).unwrap().root, "70d0a7f53a459dd88eb37c6cfdfb8c48f120e504c96b182357498f2691aa5653");
+        assert!(query(deps.as_ref(), goerli_env(), QueryMsg::Header { slot: 4359841 }).is_err());
+        assert!(query(deps.as_ref(), goerli_env(), QueryMsg::SyncCommitteePoseidon { period: 533 }).is_err());
+
+        // Updates are accepted again
+        execute(deps.as_mut(), goerli_env(), mock_info("anyone", &[]), goerli_step_msg()).unwrap();
+
+        // A healthy light client can not be re-anchored
+        let err = execute(deps.as_mut(), goerli_env(), mock_info("creator", &[]), recover_msg()).unwrap_err();
+        assert!(matches!(err, ContractError::LightClientNotFrozen {}));
+    }
+
+    #[test]
+    fn set_paused() {
+        let mut deps = mock_dependencies();
+        instantiate(deps.as_mut(), goerli_env(), mock_info("creator", &[]), goerli_instantiate_msg()).unwrap();
+
+        let err = execute(deps.as_mut(), goerli_env(), mock_info("anyone", &[]), ExecuteMsg::SetPaused { paused: true }).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        execute(deps.as_mut(), goerli_env(), mock_info("creator", &[]), ExecuteMsg::SetPaused { paused: true }).unwrap();
+
+        let err = execute(deps.as_mut(), goerli_env(), mock_info("anyone", &[]), goerli_step_msg()).unwrap_err();
+        assert!(matches!(err, ContractError::Paused {}));
+        let err = execute(deps.as_mut(), goerli_env(), mock_info("anyone", &[]), ExecuteMsg::Force { period: 532 }).unwrap_err();
+        assert!(matches!(err, ContractError::Paused {}));
+
+        // Queries are still served while paused
+        let res = query(deps.as_ref(), goerli_env(), QueryMsg::State {}).unwrap();
+        assert!(from_binary::<StateResponse>(&res).unwrap().paused);
+        query(deps.as_ref(), goerli_env(), QueryMsg::SyncCommitteePoseidon { period: 532 }).unwrap();
+
+        execute(deps.as_mut(), goerli_env(), mock_info("creator", &[]), ExecuteMsg::SetPaused { paused: false }).unwrap();
+        execute(deps.as_mut(), goerli_env(), mock_info("anyone", &[]), goerli_step_msg()).unwrap();
+    }
 
+    #[test]
+    fn two_step_ownership() {
+        let mut deps = mock_dependencies();
+        instantiate(deps.as_mut(), goerli_env(), mock_info("creator", &[]), goerli_instantiate_msg()).unwrap();
+        let owner = |deps: Deps| from_binary::<OwnerResponse>(&query(deps, goerli_env(), QueryMsg::Owner {}).unwrap()).unwrap();
+
+        let propose = ExecuteMsg::ProposeOwner { new_owner: "multisig".to_string() };
+        let err = execute(deps.as_mut(), goerli_env(), mock_info("anyone", &[]), propose.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        let err = execute(deps.as_mut(), goerli_env(), mock_info("multisig", &[]), ExecuteMsg::AcceptOwner {}).unwrap_err();
+        assert!(matches!(err, ContractError::NoPendingOwner {}));
+
+        // The creator stays in charge until the transfer is accepted
+        execute(deps.as_mut(), goerli_env(), mock_info("creator", &[]), propose).unwrap();
+        assert_eq!(owner(deps.as_ref()), OwnerResponse { owner: Some(Addr::unchecked("creator")), pending_owner: Some(Addr::unchecked("multisig")) });
+        let err = execute(deps.as_mut(), goerli_env(), mock_info("anyone", &[]), ExecuteMsg::AcceptOwner {}).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
+        execute(deps.as_mut(), goerli_env(), mock_info("multisig", &[]), ExecuteMsg::AcceptOwner {}).unwrap();
+        assert_eq!(owner(deps.as_ref()), OwnerResponse { owner: Some(Addr::unchecked("multisig")), pending_owner: None });
+        let err = execute(deps.as_mut(), goerli_env(), mock_info("creator", &[]), ExecuteMsg::SetPaused { paused: true }).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        execute(deps.as_mut(), goerli_env(), mock_info("multisig", &[]), ExecuteMsg::SetPaused { paused: true }).unwrap();
+
+        // Once renounced nobody can perform privileged operations
+        execute(deps.as_mut(), goerli_env(), mock_info("multisig", &[]), ExecuteMsg::RenounceOwner {}).unwrap();
+        assert_eq!(owner(deps.as_ref()), OwnerResponse { owner: None, pending_owner: None });
+        let err = execute(deps.as_mut(), goerli_env(), mock_info("multisig", &[]), ExecuteMsg::SetPaused { paused: false }).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+    }
+
+    #[test]
+    fn relayer_allowlist() {
+        let mut deps = mock_dependencies();
+        instantiate(deps.as_mut(), goerli_env(), mock_info("creator", &[]), goerli_instantiate_msg()).unwrap();
+
+        for msg in [
+            ExecuteMsg::SetRelayerAllowlist { enabled: true },
+            ExecuteMsg::AddRelayer { relayer: "relayer".to_string() },
+            ExecuteMsg::RemoveRelayer { relayer: "relayer".to_string() },
+        ] {
+            let err = execute(deps.as_mut(), goerli_env(), mock_info("anyone", &[]), msg).unwrap_err();
+            assert!(matches!(err, ContractError::Unauthorized {}));
+        }
+
+        execute(deps.as_mut(), goerli_env(), mock_info("creator", &[]), ExecuteMsg::AddRelayer { relayer: "relayer".to_string() }).unwrap();
+        execute(deps.as_mut(), goerli_env(), mock_info("creator", &[]), ExecuteMsg::AddRelayer { relayer: "backup".to_string() }).unwrap();
+        execute(deps.as_mut(), goerli_env(), mock_info("creator", &[]), ExecuteMsg::SetRelayerAllowlist { enabled: true }).unwrap();
+
+        let res = query(deps.as_ref(), goerli_env(), QueryMsg::ListRelayers { start_after: None, limit: None }).unwrap();
+        let relayers: ListRelayersResponse = from_binary(&res).unwrap();
+        assert!(relayers.enabled);
+        assert_eq!(relayers.relayers, vec![Addr::unchecked("backup"), Addr::unchecked("relayer")]);
+        let res = query(deps.as_ref(), goerli_env(), QueryMsg::ListRelayers { start_after: Some("backup".to_string()), limit: Some(1) }).unwrap();
+        assert_eq!(from_binary::<ListRelayersResponse>(&res).unwrap().relayers, vec![Addr::unchecked("relayer")]);
+
+        let err = execute(deps.as_mut(), goerli_env(), mock_info("anyone", &[]), goerli_step_msg()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        execute(deps.as_mut(), goerli_env(), mock_info("relayer", &[]), goerli_step_msg()).unwrap();
+
+        // Removed relayers are refused, everyone is accepted once the allowlist is disabled
+        execute(deps.as_mut(), goerli_env(), mock_info("creator", &[]), ExecuteMsg::RemoveRelayer { relayer: "relayer".to_string() }).unwrap();
+        let err = execute(deps.as_mut(), goerli_env(), mock_info("relayer", &[]), goerli_step_msg()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        execute(deps.as_mut(), goerli_env(), mock_info("creator", &[]), ExecuteMsg::SetRelayerAllowlist { enabled: false }).unwrap();
+        execute(deps.as_mut(), goerli_env(), mock_info("anyone", &[]), goerli_step_msg()).unwrap();
+    }
+
+    #[test]
+    fn relayer_rewards() {
+        let mut deps = mock_dependencies();
+        instantiate(deps.as_mut(), goerli_env(), mock_info("creator", &[]), goerli_instantiate_msg()).unwrap();
+
+        let err = execute(deps.as_mut(), goerli_env(), mock_info("funder", &coins(1000, "uosmo")), ExecuteMsg::FundRewards {}).unwrap_err();
+        assert!(matches!(err, ContractError::RewardsNotConfigured {}));
+
+        let set_rewards = ExecuteMsg::SetRewards { denom: "uosmo".to_string(), step_reward: Uint128::new(100), rotate_reward: Uint128::new(500) };
+        let err = execute(deps.as_mut(), goerli_env(), mock_info("anyone", &[]), set_rewards.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        execute(deps.as_mut(), goerli_env(), mock_info("creator", &[]), set_rewards).unwrap();
+
+        let err = execute(deps.as_mut(), goerli_env(), mock_info("funder", &coins(1000, "earth")), ExecuteMsg::FundRewards {}).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidRewardFunds { .. }));
+        execute(deps.as_mut(), goerli_env(), mock_info("funder", &coins(1000, "uosmo")), ExecuteMsg::FundRewards {}).unwrap();
+        deps.querier.update_balance(goerli_env().contract.address, coins(1000, "uosmo"));
+
+        let res = query(deps.as_ref(), goerli_env(), QueryMsg::Rewards {}).unwrap();
+        let rewards: RewardsResponse = from_binary(&res).unwrap();
+        assert_eq!(rewards.pool, Some(Coin { denom: "uosmo".to_string(), amount: Uint128::new(1000) }));
+
+        // A step advancing the head pays its relayer
+        let res = execute(deps.as_mut(), goerli_env(), mock_info("relayer", &[]), goerli_step_msg()).unwrap();
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(res.messages[0].msg, BankMsg::Send { to_address: "relayer".to_string(), amount: coins(100, "uosmo") }.into());
+
+        // Nothing is paid once the pool runs dry
+        deps.querier.update_balance(goerli_env().contract.address, coins(99, "uosmo"));
+        STATE.update(deps.as_mut().storage, |mut state| -> StdResult<_> {
+            state.head = Uint256::zero();
+            Ok(state)
+        }).unwrap();
+        let res = execute(deps.as_mut(), goerli_env(), mock_info("relayer", &[]), goerli_step_msg()).unwrap();
+        assert!(res.messages.is_empty());
+
+        // Nor for a step whose execution state root conflicts with the stored one
+        deps.querier.update_balance(goerli_env().contract.address, coins(1000, "uosmo"));
+        EXECUTION_STATE_ROOTS.save(deps.as_mut().storage, 4359840, &vec![2; 32]).unwrap();
+        STATE.update(deps.as_mut().storage, |mut state| -> StdResult<_> {
+            state.head = Uint256::zero();
+            Ok(state)
+        }).unwrap();
+        let res = execute(deps.as_mut(), goerli_env(), mock_info("relayer", &[]), goerli_step_msg()).unwrap();
+        assert!(res.messages.is_empty());
+        assert_eq!(res.events[1].ty, "inconsistency_detected");
+        assert!(!STATE.load(deps.as_ref().storage).unwrap().consistent);
+    }
+
+    #[test]
+    fn step_tracks_head() {
+        let mut deps = mock_dependencies();
+        instantiate(deps.as_mut(), goerli_env(), mock_info("creator", &[]), goerli_instantiate_msg()).unwrap();
+        let state = |deps: Deps| from_binary::<StateResponse>(&query(deps, goerli_env(), QueryMsg::State {}).unwrap()).unwrap();
+
+        execute(deps.as_mut(), goerli_env(), mock_info("anyone", &[]), goerli_step_msg()).unwrap();
+        assert_eq!(state(deps.as_ref()).head, Uint256::from(4359840u64));
+        assert_eq!(state(deps.as_ref()).head_execution_state_root, "69d746cb81cd1fb4c11f4dcc04b6114596859b518614da0dd3b4192ff66c3a58");
+
+        // Older updates are stored without moving the head back
+        STATE.update(deps.as_mut().storage, |mut state| -> StdResult<_> {
+            state.head = Uint256::from(4359900u64);
+            state.head_execution_state_root = vec![7; 32];
+            Ok(state)
+        }).unwrap();
+        execute(deps.as_mut(), goerli_env(), mock_info("anyone", &[]), goerli_step_msg()).unwrap();
+        assert_eq!(state(deps.as_ref()).head, Uint256::from(4359900u64));
+        assert_eq!(state(deps.as_ref()).head_execution_state_root, hex::encode([7; 32]));
+
+        // Unless they fall outside the configured window
+        let err = execute(deps.as_mut(), goerli_env(), mock_info("anyone", &[]), ExecuteMsg::SetMaxHeadLag { max_head_lag: Some(59) }).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        execute(deps.as_mut(), goerli_env(), mock_info("creator", &[]), ExecuteMsg::SetMaxHeadLag { max_head_lag: Some(59) }).unwrap();
+        let err = execute(deps.as_mut(), goerli_env(), mock_info("anyone", &[]), goerli_step_msg()).unwrap_err();
+        assert!(matches!(err, ContractError::UpdateTooOld { .. }));
+        execute(deps.as_mut(), goerli_env(), mock_info("creator", &[]), ExecuteMsg::SetMaxHeadLag { max_head_lag: Some(60) }).unwrap();
+        execute(deps.as_mut(), goerli_env(), mock_info("anyone", &[]), goerli_step_msg()).unwrap();
+    }
+
+    #[test]
+    fn slot_clock() {
+        let current_slot = |genesis_time: u32, seconds_per_slot: u32, now: u64| {
+            let mut deps = mock_dependencies();
+            let mut msg = goerli_instantiate_msg();
+            msg.genesis_time = genesis_time;
+            msg.seconds_per_slot = seconds_per_slot;
+            instantiate(deps.as_mut(), env_at(now), mock_info("creator", &[]), msg).unwrap();
+            query(deps.as_ref(), env_at(now), QueryMsg::GetCurrentSlot {})
+                .map(|res| from_binary::<GetCurrentSlotResponse>(&res).unwrap().slot)
+        };
+
+        // Mainnet, 12 second slots
+        assert_eq!(current_slot(1606824023, 12, 1606824023).unwrap(), Uint256::zero());
+        assert_eq!(current_slot(1606824023, 12, 1606824023 + 11).unwrap(), Uint256::zero());
+        assert_eq!(current_slot(1606824023, 12, 1606824023 + 12 * 6209536 + 5).unwrap(), Uint256::from(6209536u64));
+        // Gnosis, 5 second slots
+        assert_eq!(current_slot(1638993340, 5, 1638993340 + 5 * 7600000 + 4).unwrap(), Uint256::from(7600000u64));
+        // Before genesis there is no slot
+        assert!(current_slot(1638993340, 5, 1638993339).is_err());
+    }
+
+    #[test]
+    fn step_clock_drift() {
+        let mut deps = mock_dependencies();
+        let mut msg = goerli_instantiate_msg();
+        msg.max_future_slots = Some(2);
+        instantiate(deps.as_mut(), goerli_env(), mock_info("creator", &[]), msg).unwrap();
+
+        // Slot 4359840 starts at 1668826080
+        let err = execute(deps.as_mut(), env_at(1668826080 - 36), mock_info("anyone", &[]), goerli_step_msg()).unwrap_err();
+        assert!(matches!(err, ContractError::UpdateSlotTooFar {}));
+        execute(deps.as_mut(), env_at(1668826080 - 24), mock_info("anyone", &[]), goerli_step_msg()).unwrap();
+
+        // The owner can widen the tolerance later on
+        let err = execute(deps.as_mut(), goerli_env(), mock_info("anyone", &[]), ExecuteMsg::SetMaxFutureSlots { max_future_slots: 3 }).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        execute(deps.as_mut(), goerli_env(), mock_info("creator", &[]), ExecuteMsg::SetMaxFutureSlots { max_future_slots: 3 }).unwrap();
+        let res = query(deps.as_ref(), goerli_env(), QueryMsg::State {}).unwrap();
+        assert_eq!(from_binary::<StateResponse>(&res).unwrap().max_future_slots, Uint256::from(3u64));
+        execute(deps.as_mut(), env_at(1668826080 - 36), mock_info("anyone", &[]), goerli_step_msg()).unwrap();
+
+        // Before genesis there is no current slot to compare against
+        let err = execute(deps.as_mut(), env_at(1616508000 - 1), mock_info("anyone", &[]), goerli_step_msg()).unwrap_err();
+        assert!(matches!(err, ContractError::BeforeGenesis { time: 1616507999, .. }));
+    }
+
+    #[test]
+    fn force_best_update() {
+        let mut deps = mock_dependencies();
+        instantiate(deps.as_mut(), goerli_env(), mock_info("creator", &[]), goerli_instantiate_msg()).unwrap();
+        let eligibility = |deps: Deps, env: Env| {
+            from_binary::<ForceEligibilityResponse>(&query(deps, env, QueryMsg::ForceEligibility { period: 532 }).unwrap()).unwrap()
+        };
+        assert!(!eligibility(deps.as_ref(), goerli_env()).best_update_exists);
+
+        let update = LightClientRotate {
+            step: LightClientStep {
+                finalized_slot: Uint256::from(4360032u64),
+                participation: Uint256::from(300u64),
+                finalized_header_root: vec![1; 32],
+                execution_state_root: vec![2; 32],
+                proof: Groth16Proof { a: vec![], b: vec![], c: vec![] },
+            },
+            sync_committee_ssz: vec![3; 32],
+            sync_committee_poseidon: Uint256::from(42u64).to_le_bytes().to_vec(),
+            proof: Groth16Proof { a: vec![], b: vec![], c: vec![] },
+        };
+        BEST_UPDATES.save(deps.as_mut().storage, 532, &update).unwrap();
+
+        // Period 533 starts at slot 4366336
+        let period_end = 1616508000 + 4366336 * 12;
+        let res = eligibility(deps.as_ref(), goerli_env());
+        assert_eq!(res.participation, Some(Uint256::from(300u64)));
+        assert_eq!(res.earliest_force_slot, Uint256::from(4366336u64));
+        assert!(!res.eligible);
+        let err = execute(deps.as_mut(), env_at(period_end - 1), mock_info("keeper", &[]), ExecuteMsg::Force { period: 532 }).unwrap_err();
+        assert!(matches!(err, ContractError::CurrentSyncCommitteeNotEnded {}));
+
+        assert!(eligibility(deps.as_ref(), env_at(period_end)).eligible);
+        execute(deps.as_mut(), env_at(period_end), mock_info("keeper", &[]), ExecuteMsg::Force { period: 532 }).unwrap();
+        let res = query(deps.as_ref(), goerli_env(), QueryMsg::SyncCommitteePoseidon { period: 533 }).unwrap();
+        assert_eq!(from_binary::<SyncCommitteePoseidonResponse>(&res).unwrap().poseidon, Uint256::from(42u64));
+
+        let res = eligibility(deps.as_ref(), env_at(period_end));
+        assert!(res.next_sync_committee_initialized && !res.eligible);
+        let err = execute(deps.as_mut(), env_at(period_end), mock_info("keeper", &[]), ExecuteMsg::Force { period: 532 }).unwrap_err();
+        assert!(matches!(err, ContractError::SyncCommitteeAlreadyInitialized {}));
+    }
+
+    #[test]
+    fn prove_historical_header() {
+        let mut deps = mock_dependencies();
+        let mut msg = goerli_instantiate_msg();
+        // Goerli Capella fork, the first slot of era 634
+        msg.capella_fork_slot = Some(5193728);
+        instantiate(deps.as_mut(), goerli_env(), mock_info("creator", &[]), msg).unwrap();
+
+        // Synthetic branches, the stored header roots are rebuilt from the target root
+        use crate::ssz::{gindex_depth, restore_merkle_root};
+        let target_root = [0xaa; 32];
+        let store_header = |deps: &mut cosmwasm_std::OwnedDeps<_, _, _>, slot: u64, index: u64| {
+            let branch: Vec<[u8; 32]> = (0..gindex_depth(index)).map(|i| [i as u8; 32]).collect();
+            let root = restore_merkle_root(&target_root, index, &branch);
+            HEADERS.save(deps.as_mut().storage, slot, &root.to_vec()).unwrap();
+            branch.iter().map(hex::encode).collect::<Vec<_>>()
+        };
+        let prove = |slot: u64, target_slot: u64, branch: &[String]| ExecuteMsg::ProveHistoricalHeader {
+            slot,
+            target_slot,
+            target_root: hex::encode(target_root),
+            branch: branch.to_vec(),
+        };
+
+        // Through block_roots, up to SLOTS_PER_HISTORICAL_ROOT slots back
+        let slot = 5242980;
+        let target_slot = slot - 8192;
+        let branch = store_header(&mut deps, slot, concat_gindex(HEADER_STATE_ROOT_INDEX, block_roots_index(target_slot)));
+        assert_eq!(21, branch.len());
+
+        // Subject to the same pause and relayer allowlist as steps
+        execute(deps.as_mut(), goerli_env(), mock_info("creator", &[]), ExecuteMsg::SetPaused { paused: true }).unwrap();
+        let err = execute(deps.as_mut(), goerli_env(), mock_info("anyone", &[]), prove(slot, target_slot, &branch)).unwrap_err();
+        assert!(matches!(err, ContractError::Paused {}));
+        execute(deps.as_mut(), goerli_env(), mock_info("creator", &[]), ExecuteMsg::SetPaused { paused: false }).unwrap();
+        execute(deps.as_mut(), goerli_env(), mock_info("creator", &[]), ExecuteMsg::SetRelayerAllowlist { enabled: true }).unwrap();
+        let err = execute(deps.as_mut(), goerli_env(), mock_info("anyone", &[]), prove(slot, target_slot, &branch)).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        execute(deps.as_mut(), goerli_env(), mock_info("creator", &[]), ExecuteMsg::SetRelayerAllowlist { enabled: false }).unwrap();
+
+        let res = execute(deps.as_mut(), goerli_env(), mock_info("anyone", &[]), prove(slot, target_slot, &branch)).unwrap();
+        assert_eq!(res.events, vec![Event::new("historical_header_proven")
+            .add_attribute("slot", target_slot.to_string())
+            .add_attribute("root", hex::encode(target_root))
+            .add_attribute("source_slot", slot.to_string())]);
+        let res = query(deps.as_ref(), goerli_env(), QueryMsg::BlockRoot { slot: target_slot }).unwrap();
+        assert_eq!(from_binary::<HeaderResponse>(&res).unwrap().root, hex::encode(target_root));
+        let res = query(deps.as_ref(), goerli_env(), QueryMsg::State {}).unwrap();
+        assert_eq!(from_binary::<StateResponse>(&res).unwrap().head, Uint256::zero());
+
+        // Proven roots are kept apart from the verified headers, which take precedence
+        assert!(query(deps.as_ref(), goerli_env(), QueryMsg::Header { slot: target_slot }).is_err());
+        HEADERS.save(deps.as_mut().storage, target_slot, &vec![1; 32]).unwrap();
+        let res = query(deps.as_ref(), goerli_env(), QueryMsg::BlockRoot { slot: target_slot }).unwrap();
+        assert_eq!(from_binary::<HeaderResponse>(&res).unwrap().root, hex::encode([1; 32]));
+        HEADERS.remove(deps.as_mut().storage, target_slot);
+
+
+        // Same block_roots entry one era earlier, which is only reachable through the summaries
+        let err = execute(deps.as_mut(), goerli_env(), mock_info("anyone", &[]), prove(slot, target_slot - 8192, &branch)).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidMerkleBranch { .. }));
+        let mut tampered = branch.clone();
+        tampered[20] = hex::encode([0xff; 32]);
+        let err = execute(deps.as_mut(), goerli_env(), mock_info("anyone", &[]), prove(slot, target_slot + 1, &tampered)).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidMerkleBranch { .. }));
+
+        // A skipped slot resolves to the root of the block before it, which does not conflict
+        // with the header a later step verifies for that slot
+        let skipped_slot = target_slot + 1;
+        let branch = store_header(&mut deps, slot, concat_gindex(HEADER_STATE_ROOT_INDEX, block_roots_index(skipped_slot)));
+        execute(deps.as_mut(), goerli_env(), mock_info("anyone", &[]), prove(slot, skipped_slot, &branch)).unwrap();
+        let res = query(deps.as_ref(), goerli_env(), QueryMsg::BlockRoot { slot: skipped_slot }).unwrap();
+        assert_eq!(from_binary::<HeaderResponse>(&res).unwrap().root, hex::encode(target_root));
+        set_head(deps.as_mut(), &Addr::unchecked("relayer"), Uint256::from(skipped_slot), vec![1; 32]).unwrap();
+        let res = query(deps.as_ref(), goerli_env(), QueryMsg::State {}).unwrap();
+        assert!(from_binary::<StateResponse>(&res).unwrap().consistent);
+
+        // Through the historical summary of era 637, the fourth since Capella
+        let slot = 5242981;
+        let target_slot = 5193728 + 3 * 8192 + 5;
+        let branch = store_header(&mut deps, slot, concat_gindex(HEADER_STATE_ROOT_INDEX, historical_block_root_index(3, target_slot)));
+        assert_eq!(47, branch.len());
+        execute(deps.as_mut(), goerli_env(), mock_info("anyone", &[]), prove(slot, target_slot, &branch)).unwrap();
+        let res = query(deps.as_ref(), goerli_env(), QueryMsg::BlockRoot { slot: target_slot }).unwrap();
+        assert_eq!(from_binary::<HeaderResponse>(&res).unwrap().root, hex::encode(target_root));
+
+        // Before Capella, at or after the stored slot, or from a slot without a header
+        let err = execute(deps.as_mut(), goerli_env(), mock_info("anyone", &[]), prove(slot, 5193727, &branch)).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidHistoricalSlot { .. }));
+        let err = execute(deps.as_mut(), goerli_env(), mock_info("anyone", &[]), prove(slot, slot, &branch)).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidHistoricalSlot { .. }));
+        assert!(execute(deps.as_mut(), goerli_env(), mock_info("anyone", &[]), prove(slot + 1, target_slot, &branch)).is_err());
+
+        // A different root proven for the same slot freezes the light client
+        let index = concat_gindex(HEADER_STATE_ROOT_INDEX, historical_block_root_index(3, target_slot));
+        let conflicting_branch: Vec<[u8; 32]> = (0..gindex_depth(index)).map(|i| [i as u8; 32]).collect();
+        HEADERS.save(deps.as_mut().storage, slot + 1, &restore_merkle_root(&[0xbb; 32], index, &conflicting_branch).to_vec()).unwrap();
+        let res = execute(deps.as_mut(), goerli_env(), mock_info("relayer", &[]), ExecuteMsg::ProveHistoricalHeader {
+            slot: slot + 1,
+            target_slot,
+            target_root: hex::encode([0xbb; 32]),
+            branch: conflicting_branch.iter().map(hex::encode).collect(),
+        }).unwrap();
+        assert_eq!(res.events[0].ty, "inconsistency_detected");
+        let res = query(deps.as_ref(), goerli_env(), QueryMsg::InconsistencyEvidence {}).unwrap();
+        assert_eq!(from_binary::<InconsistencyEvidenceResponse>(&res).unwrap().evidence, vec![InconsistencyEvidence {
+            kind: InconsistencyKind::HistoricalBlockRoot,
+            key: Uint256::from(target_slot),
+            existing_root: hex::encode(target_root),
+            new_root: hex::encode([0xbb; 32]),
+            submitter: Addr::unchecked("relayer"),
+        }]);
+        assert!(query(deps.as_ref(), goerli_env(), QueryMsg::BlockRoot { slot: target_slot }).is_err());
+
+        // Only block_roots without the fork slot
+        let mut deps = mock_dependencies();
+        instantiate(deps.as_mut(), goerli_env(), mock_info("creator", &[]), goerli_instantiate_msg()).unwrap();
+        let branch = store_header(&mut deps, slot, concat_gindex(HEADER_STATE_ROOT_INDEX, historical_block_root_index(3, target_slot)));
+        let err = execute(deps.as_mut(), goerli_env(), mock_info("anyone", &[]), prove(slot, target_slot, &branch)).unwrap_err();
+        assert!(matches!(err, ContractError::CapellaForkSlotNotSet {}));
+
+        // Until the owner sets it
+        let set_fork_slot = ExecuteMsg::SetCapellaForkSlot { capella_fork_slot: Some(5193728) };
+        let err = execute(deps.as_mut(), goerli_env(), mock_info("anyone", &[]), set_fork_slot.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        execute(deps.as_mut(), goerli_env(), mock_info("creator", &[]), set_fork_slot).unwrap();
+        let res = query(deps.as_ref(), goerli_env(), QueryMsg::State {}).unwrap();
+        assert_eq!(from_binary::<StateResponse>(&res).unwrap().capella_fork_slot, Some(Uint256::from(5193728u64)));
+        execute(deps.as_mut(), goerli_env(), mock_info("anyone", &[]), prove(slot, target_slot, &branch)).unwrap();
     }
 }
@@ -15,6 +15,9 @@ pub enum ContractError {
     #[error("Update slot is too far in the future")]
     UpdateSlotTooFar {},
 
+    #[error("Block time {time} is before genesis at {genesis_time}")]
+    BeforeGenesis { time: u64, genesis_time: String },
+
     #[error("Less than MIN_SYNC_COMMITTEE_PARTICIPANTS signed.")]
     NotEnoughSyncCommitteeParticipants {},
 
@@ -36,6 +39,81 @@ pub enum ContractError {
     #[error("Rotate proof failed to verify.")]
     InvalidRotateProof {},
 
+    #[error("Invalid verifying key for the {circuit} circuit: {reason}")]
+    InvalidVerifyingKey { circuit: String, reason: String },
+
+    #[error("Invalid hex string for {field}")]
+    InvalidHex { field: String },
+
+    #[error("Invalid length for {field}: expected {expected} bytes, got {got}")]
+    InvalidRootLength { field: String, expected: usize, got: usize },
+
+    #[error("Invalid field element for {field}: {value}")]
+    InvalidFieldElement { field: String, value: String },
+
+    #[error("Invalid number of coordinates for {field}: expected {expected}, got {got}")]
+    InvalidCoordinateCount { field: String, expected: usize, got: usize },
+
+    #[error("Point {point} is not on the curve")]
+    PointNotOnCurve { point: String },
+
+    #[error("Coordinate of {field} is not below the base field modulus: {value}")]
+    CoordinateOutOfRange { field: String, value: String },
+
+    #[error("Point {point} is not in the prime order subgroup")]
+    PointNotInSubgroup { point: String },
+
+    #[error("Verifying key expects {expected} public inputs, got {got}")]
+    InvalidPublicInputCount { expected: usize, got: usize },
+
+    #[error("Public input {field} is not a canonical scalar field element: {value}")]
+    NonCanonicalPublicInput { field: String, value: String },
+
+    #[error("Cannot migrate from contract {contract}")]
+    CannotMigrate { contract: String },
+
+    #[error("Cannot migrate from version {from} down to {to}")]
+    CannotDowngrade { from: String, to: String },
+
+    #[error("Light client is frozen after conflicting roots were detected")]
+    LightClientFrozen {},
+
+    #[error("Light client is not frozen, there is nothing to recover from")]
+    LightClientNotFrozen {},
+
+    #[error("Recovering to slot {head_slot} behind the head {head} requires pruning the roots after it")]
+    RecoverWithoutPrune { head_slot: String, head: String },
+
+    #[error("Light client updates are paused")]
+    Paused {},
+
+    #[error("No ownership transfer is pending")]
+    NoPendingOwner {},
+
+    #[error("Relayer rewards are not configured")]
+    RewardsNotConfigured {},
+
+    #[error("Rewards must be funded with {denom} only")]
+    InvalidRewardFunds { denom: String },
+
+    #[error("Update for slot {slot} is too far behind the head at slot {head}")]
+    UpdateTooOld { slot: String, head: String },
+
+    #[error("Header for slot {target_slot} can not be proven from slot {slot}")]
+    InvalidHistoricalSlot { slot: u64, target_slot: u64 },
+
+    #[error("Capella fork slot is not set, headers older than 8192 slots can not be proven")]
+    CapellaForkSlotNotSet {},
+
+    #[error("Invalid Merkle branch for {field}")]
+    InvalidMerkleBranch { field: String },
+
+    #[error("Migration requires {field}")]
+    MissingMigrateField { field: String },
+
+    #[error("{field} must be greater than zero")]
+    ZeroValue { field: String },
+
     #[error("Custom Error val: {val:?}")]
     CustomError { val: String },
     // Add any other custom errors you like here.
@@ -0,0 +1,87 @@
+use cosmwasm_std::{Event, Uint256};
+
+use crate::state::InconsistencyEvidence;
+
+// Event types and attribute keys are part of the public interface relayers and
+// indexers subscribe to. CosmWasm prefixes custom event types with `wasm-`.
+pub const HEAD_UPDATE: &str = "head_update";
+pub const SYNC_COMMITTEE_UPDATE: &str = "sync_committee_update";
+pub const EXECUTION_STATE_ROOT_UPDATE: &str = "execution_state_root_update";
+pub const INCONSISTENCY_DETECTED: &str = "inconsistency_detected";
+pub const LIGHT_CLIENT_RECOVERED: &str = "light_client_recovered";
+pub const HISTORICAL_HEADER_PROVEN: &str = "historical_header_proven";
+
+pub const ATTR_SLOT: &str = "slot";
+pub const ATTR_PERIOD: &str = "period";
+pub const ATTR_ROOT: &str = "root";
+pub const ATTR_KIND: &str = "kind";
+pub const ATTR_EXISTING_ROOT: &str = "existing_root";
+pub const ATTR_NEW_ROOT: &str = "new_root";
+pub const ATTR_SUBMITTER: &str = "submitter";
+pub const ATTR_HEAD_SLOT: &str = "head_slot";
+pub const ATTR_PRUNED: &str = "pruned";
+pub const ATTR_SOURCE_SLOT: &str = "source_slot";
+
+/*
+* @dev Mirrors `HeadUpdate(slot, root)`. The root is hex encoded.
+*/
+pub fn head_update(slot: Uint256, root: &[u8]) -> Event {
+    Event::new(HEAD_UPDATE)
+        .add_attribute(ATTR_SLOT, slot.to_string())
+        .add_attribute(ATTR_ROOT, hex::encode(root))
+}
+
+/*
+* @dev Mirrors `SyncCommitteeUpdate(period, root)`. The root is the decimal poseidon
+* commitment, in the same format it is submitted and queried in.
+*/
+pub fn sync_committee_update(period: Uint256, poseidon: Uint256) -> Event {
+    Event::new(SYNC_COMMITTEE_UPDATE)
+        .add_attribute(ATTR_PERIOD, period.to_string())
+        .add_attribute(ATTR_ROOT, poseidon.to_string())
+}
+
+/*
+* @dev Emitted when the execution state root for a slot is stored. The root is hex encoded.
+*/
+pub fn execution_state_root_update(slot: Uint256, root: &[u8]) -> Event {
+    Event::new(EXECUTION_STATE_ROOT_UPDATE)
+        .add_attribute(ATTR_SLOT, slot.to_string())
+        .add_attribute(ATTR_ROOT, hex::encode(root))
+}
+
+/*
+* @dev Emitted when a verified root conflicts with the one already stored. `kind` is one of
+* `header`, `execution_state_root`, `sync_committee` or `historical_block_root`, followed by
+* the `slot` or `period` the conflicting entry is keyed by.
+*/
+pub fn inconsistency_detected(evidence: &InconsistencyEvidence) -> Event {
+    Event::new(INCONSISTENCY_DETECTED)
+        .add_attribute(ATTR_KIND, evidence.kind.as_str())
+        .add_attribute(evidence.kind.key_name(), evidence.key.to_string())
+        .add_attribute(ATTR_EXISTING_ROOT, evidence.existing_root.clone())
+        .add_attribute(ATTR_NEW_ROOT, evidence.new_root.clone())
+        .add_attribute(ATTR_SUBMITTER, evidence.submitter.to_string())
+}
+
+/*
+* @dev Emitted when governance re-anchors the light client. `pruned` counts the stored
+* entries removed after the new anchor.
+*/
+pub fn light_client_recovered(period: Uint256, head_slot: Uint256, pruned: usize) -> Event {
+    Event::new(LIGHT_CLIENT_RECOVERED)
+        .add_attribute(ATTR_PERIOD, period.to_string())
+        .add_attribute(ATTR_HEAD_SLOT, head_slot.to_string())
+        .add_attribute(ATTR_PRUNED, pruned.to_string())
+}
+
+/*
+* @dev Emitted when the root of an older slot is proven against the verified header of
+* `source_slot`. Unlike `head_update` it never moves the head. The root is hex encoded.
+*/
+pub fn historical_header_proven(slot: Uint256, root: &[u8], source_slot: Uint256) -> Event {
+    Event::new(HISTORICAL_HEADER_PROVEN)
+        .add_attribute(ATTR_SLOT, slot.to_string())
+        .add_attribute(ATTR_ROOT, hex::encode(root))
+        .add_attribute(ATTR_SOURCE_SLOT, source_slot.to_string())
+}
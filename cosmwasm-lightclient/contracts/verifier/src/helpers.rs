@@ -1,531 +1,78 @@
-use ark_groth16::{prepare_verifying_key, Proof, VerifyingKey, verify_proof};
+use ark_ec::PairingEngine;
+use ark_ff::{BigInteger256, PrimeField};
+use ark_groth16::{prepare_verifying_key, PreparedVerifyingKey, Proof, VerifyingKey, verify_proof};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
 use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{StdError, StdResult, Uint256};
 
 
 
-use ark_bn254::{Bn254, Fq, Fq2, Fr, G1Affine, G1Projective, G2Affine, G2Projective};
+use ark_bn254::{Bn254, Fq, Fq2, Fq12, Fr, G1Affine, G1Projective, G2Affine, G2Projective};
+use sha2::{Digest, Sha256};
 use std::str::FromStr;
 
-#[cw_serde]
+use crate::ContractError;
+
+type G2Prepared = <Bn254 as PairingEngine>::G2Prepared;
+
 pub struct Verifier {
-    vk_json: VerifyingKeyJson,
+    pvk: PreparedVerifyingKey<Bn254>,
 }
 
 impl Verifier {
-    pub fn new_step_verifier() -> Self {
-        Self {
-            vk_json: VerifyingKeyJson { 
-              ic: vec![
-                vec![
-                "14768330346746297840816367070658728893313212053739352195802618469166531204391".to_string(),
-                "226007277514949219964518589190903213280753732819328898150443666757283640566".to_string(),
-                "1".to_string(),
-                ],
-                vec![
-                "11579789275084599412171695990815953848893751967864880119324773293908098730772".to_string(),
-                "7016524000863123597202679959446996204295974709290664682467334394757983209848".to_string(),
-                "1".to_string(),
-                ],
-                ], 
-              vk_alpha_1: vec!["20491192805390485299153009773594534940189261866228447918068658471970481763042".to_string(),
-              "9383485363053290200918347156157836566562967994039712273449902621266178545958".to_string(),
-              "1".to_string()], 
-              vk_beta_2: vec![
-              vec![
-              "6375614351688725206403948262868962793625744043794305715222011528459656738731".to_string(),
-              "4252822878758300859123897981450591353533073413197771768651442665752259397132".to_string(),
-              ],
-              vec![
-              "10505242626370262277552901082094356697409835680220590971873171140371331206856".to_string(),
-              "21847035105528745403288232691147584728191162732299865338377159692350059136679".to_string(),
-              ],
-              vec![
-              "1".to_string(),
-              "0".to_string(),
-              ],
-              ], 
-              vk_gamma_2: vec![
-                vec![
-                "10857046999023057135944570762232829481370756359578518086990519993285655852781".to_string(),
-                "11559732032986387107991004021392285783925812861821192530917403151452391805634".to_string(),
-                ],
-                vec![
-                "8495653923123431417604973247489272438418190587263600148770280649306958101930".to_string(),
-                "4082367875863433681332203403145435568316851327593401208105741076214120093531".to_string(),
-                ],
-                vec![
-                "1".to_string(),
-                "0".to_string(),
-                ],
-                ], 
-              vk_delta_2: vec![
-                vec![
-                "13909124302531010921185816266702828674819977847946098152869315744616458486564".to_string(),
-                "20132301864891590102651537900097603129841488311097169471951837821863335966377".to_string(),
-                ],
-                vec![
-                "9968363667543645393414941586581030294599633785037951467496223618072496422152".to_string(),
-                "19620890790369364323423864638476333921325558259845161848280036523505618212219".to_string(),
-                ],
-                vec![
-                "1".to_string(),
-                "0".to_string(),
-                ],
-                ], 
-              vk_alphabeta_12: vec![
-                vec![
-                vec![
-                "2029413683389138792403550203267699914886160938906632433982220835551125967885".to_string(),
-                "21072700047562757817161031222997517981543347628379360635925549008442030252106".to_string(),
-                ],
-                vec![
-                "5940354580057074848093997050200682056184807770593307860589430076672439820312".to_string(),
-                "12156638873931618554171829126792193045421052652279363021382169897324752428276".to_string(),
-                ],
-                vec![
-                "7898200236362823042373859371574133993780991612861777490112507062703164551277".to_string(),
-                "7074218545237549455313236346927434013100842096812539264420499035217050630853".to_string(),
-                ],
-                ],
-                vec![
-                vec![
-                "7077479683546002997211712695946002074877511277312570035766170199895071832130".to_string(),
-                "10093483419865920389913245021038182291233451549023025229112148274109565435465".to_string(),
-                ],
-                vec![
-                "4595479056700221319381530156280926371456704509942304414423590385166031118820".to_string(),
-                "19831328484489333784475432780421641293929726139240675179672856274388269393268".to_string(),
-                ],
-                vec![
-                "11934129596455521040620786944827826205713621633706285934057045369193958244500".to_string(),
-                "8037395052364110730298837004334506829870972346962140206007064471173334027475".to_string(),
-                ],
-                ],
-                ], 
-              inputs_count: 1, 
-              curve: "bn128".to_string(), 
-              protocol: "groth16".to_string() },
-        }
+    pub fn new(vk_json: VerifyingKeyJson) -> Result<Self, ContractError> {
+        let vk = vk_json.to_verifying_key()?;
+        Ok(Self { pvk: prepare_verifying_key(&vk) })
     }
-    pub fn new_rotate_verifier() -> Self {
-        Self {
-            vk_json: VerifyingKeyJson { ic: vec![
-              vec![
-              "8578436021932201623189950508428893454182388340351344018505166330143567388321".to_string(),
-              "16791589135888029668423043760081149899869808657673802901072541038338364024546".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "25701822399942772837622355873666773966584557696246630986145745837743012328".to_string(),
-              "9106296548924249216714445588642408292162429818431200049858166820587553300743".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "3887662612813233731242849276337128617088918625889507185164703631018117727457".to_string(),
-              "13896811173741308528708014989807693837248126243365939233014522115172481095858".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "5548374792924448906382503954713620545264383960034078132831347681208659883879".to_string(),
-              "18337979253751456511617696979491402846813972161597496106673851097218050546456".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "3513238722103274273522406813717310357132624410145966651049892804577998022250".to_string(),
-              "2100309871897719369243436360393091182026895390103977792742478850790465589269".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "16774465038985513133855420166203428339905539044232825240202423848107973332678".to_string(),
-              "16725209702452907720642671886251029659390071036535497310393123580872513582854".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "12723222834246347599931898986533294650712552047289490705825258098822663181624".to_string(),
-              "15583690586388405262832138004749201253661764369810956711623986951827016385530".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "5026046789904582550128325004633260710365247286992446247401846497370999862747".to_string(),
-              "4819305538846217744687809604109550399579898666648742504258146150555058505951".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "10661978866672543270025821549099960066183206641640607583795628600555660041044".to_string(),
-              "2221705381270113399691209125100432726126638608264735073397284796557721989416".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "14569572986724347962216163519300364172959753116406286139655301725097646105213".to_string(),
-              "7770341587360597134866306712713249203225481402263847954302862733679631296332".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "12051848431286534627025225721668847872601656750023602432136473514335384207905".to_string(),
-              "16398462036535521278977084915557583121149215752915386948180254636830456435800".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "17788516549705187249706048705237434803985726058264305192488661365056352231811".to_string(),
-              "9833324865779724950328599003907366233834043080911289073114570858263518851267".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "13791407112521867690666798946890378866880739926855704351528800097495737005313".to_string(),
-              "9534280154764496754119106345378471704562751105412798642818003985309684155151".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "11248157595270537118518876790672097520286458263406838461943898126202312022003".to_string(),
-              "16525335969593025378236490977703788022845534987776992759419681152792801933150".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "7642481625425142796479825182895684552997460824738481219118229232519255016122".to_string(),
-              "8250883196634402505522270306684594922079910587826698471154644440215155175065".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "20425417013275916359632478635869739544705063913976799283081298827713835008110".to_string(),
-              "20477302060644465575718455696912324317560351559288633190546290046579607127782".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "5425654060952217831080366579755421820472382281006453564161764015857007147308".to_string(),
-              "2950774582227936811007364319920842851514992726670461455045056439315868976302".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "5345436432687112692120516705320110649658622123412198897333887448750105754094".to_string(),
-              "12300050264583876513872817905211693972346409799324322143353629794626148303442".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "12973751590041412652384666204593322273486088127424548185584690698646088515366".to_string(),
-              "19439454078430752010862439376052076513472883096982623546597398436535919207925".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "4051955477657354364034588331948693118288989339139747954526800546287590433367".to_string(),
-              "8422113484412961376284807099601639543331373228327596131273344384708959637800".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "16034208249619483528537324662673305101108919627658789222516455169942322626890".to_string(),
-              "2345370686898341341833172192052081748607196864441662005601752842695199604273".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "7162278218711168144539456933597029036462883651507713370483520258091030047429".to_string(),
-              "93445815624105727412582372167300715983431681136591221819840916128859591933".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "17214438362215194946528883491695011285608827300245652564670983209636706940975".to_string(),
-              "16977413402930132070461984996254832883263273928993725459833681575765046188153".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "3591514883641765161254179883208720849262342830862751841947669343164678820135".to_string(),
-              "16022324464419654289219272969977271672139780078813781022874177286765631712277".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "3903335409076464950016842146214318381616743200272768251329673263329445521934".to_string(),
-              "8822745067031219301073330395613443753011843342528653757842007857532252394897".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "20963169553880060624744369756206285183771006478629749548259884282180481836534".to_string(),
-              "20279716059842296973063211782744920064881111010681935031261606985597860986180".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "5095299918745785358235521759906589570981860067522840247916967430706086094557".to_string(),
-              "12133158724583717328166109840120375488573482221575289550453667718133976528711".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "6252243763528887479655373972663605655248654166160168398252973914846559499451".to_string(),
-              "19695240308799025883820642095687086351053987273168071182002606925852289407370".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "4750713164113859748632553187685803530787446088636347100756780854479883104015".to_string(),
-              "16524898010325066819175496974692004268392693095676683693888933219400161831678".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "8475730413189097684199719897159359674549695479833187546363581091151973456000".to_string(),
-              "6575024985438886780945419412068983299568680022782503793060009594499344573170".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "16931660422068331333426421256087717976475391781393516266616339605910942675799".to_string(),
-              "10798018304952842957642633299117637424880355563081929085194665026052280411608".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "17108305426117213840473123358110156865232158305904967287011703547445123818610".to_string(),
-              "4942568915479502343383295887000935910860792051791900836046749297657581173695".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "3053330074625595859032994119330264137217008186816143155877315943458516609723".to_string(),
-              "10928277854685557763504975592825605803473667433076434954239097747587556401639".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "14260996190257301724307327857473614480455053118165499642543870884065213664802".to_string(),
-              "5781043356584800534083724257045871515554831431206130331874662035818208730916".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "13317606427169277258741630922600625957145741985911619244480545112853676645090".to_string(),
-              "572783024506068253346259078489068696797043226601655240843070730803225057046".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "18833598692940968004238571946510816466217783250926189461491507496528773063245".to_string(),
-              "9803047670579031865037220222352573557736479776637366113346841421108087024999".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "16863963222995263202348067448801398078392829688720499342469521212653985602487".to_string(),
-              "5519825027928147361149571760747081306325606883566669488697271744024490337713".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "3290182205014312303396202753032137899158438221048761947449055699102872760091".to_string(),
-              "15851623347937411436723386196039681426964692538377995126973930110879276421219".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "4864368932784106363139200909803344009482582568508303840147146481653699687116".to_string(),
-              "18819105934616626883514776813955507376198415537298913546567378213336674500185".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "11621424319863845876772693521251285267934330301013583282886665290577107047754".to_string(),
-              "14757629890330056529060446934608077240991821050049361571332295098162959608138".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "13313378820527301676075398954384468626068276316581551700749216148064958870827".to_string(),
-              "4570686841791817360077052993124537204919387072413391380697780106447560304021".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "15129484242473909982576722937346346137003248414667516042498463392561426333395".to_string(),
-              "11521943800904290626919156263047047983059520411337770928798239813991641618194".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "20961904026923155720762616161576867868311128903756310726865635905664533823460".to_string(),
-              "622058629612433662647233277839533079818226833910358972024383668685579913266".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "1533622366571790629947432921314140452761040756481885352413149272711767937295".to_string(),
-              "17114490941702232460276930767791689779218278654921328370533051193019480334013".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "3872345698339374374262636133638238401218696068128991467025014085621554417288".to_string(),
-              "5022659195638806340461724291337269719146700577992879438615182782548662972273".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "1892716585138150130125401193013638707197028162928516613379540202811516362219".to_string(),
-              "5488777164986426669680999859949800986314280191938087219824281589742727483623".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "14723470064749399568227840255239556884827479726776670054549910646833921784397".to_string(),
-              "9850312122776445437928702910599449626324211657013931974572474877352706350126".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "9950769240264532726070836771187757315842167364102348677135477949114681025079".to_string(),
-              "3408332309304167992372902937582832393896979057510540217526111203670003315844".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "18176981554172428201599098550569359991916865570367066111740022950649267830425".to_string(),
-              "1504425020122023771905878765060423416139787562985474457883000913071726901061".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "8025346228433260850278166492321799112421131416178993792301266218315749244869".to_string(),
-              "18003902846005525205604781629324908788035926576838595439192554527651062180881".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "7657371219832862030178333533911732352748584695586346243395458915778658564707".to_string(),
-              "15526170180937420482258756256789494136225570243659664056782104842571677681737".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "1422092002462125993634365017842944329542993521984208494589273271206097414227".to_string(),
-              "19995906694386773441868898434490466214575501668430787350691378128976533171434".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "13549896273100282761212147161568408582278060205778138880736758099020613619123".to_string(),
-              "14866480605200774667680263996986607619880834833436959561215039626981432470438".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "3994741454453578842507365286256147027842650417717422946550104243267962330526".to_string(),
-              "20285002847260145366360747410192787771887613258937008542290569287903227281096".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "13013905821442600236291763236258646172544367104636592517338414430282798068451".to_string(),
-              "88446066118379034768219930198938867468445931864040254837930141662737233637".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "13626065954646412358003398584868006948208810234372685348960415372826734657148".to_string(),
-              "7801769257271052343527026775182064085614220780378411058204096830467909423973".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "8180814095472527088987445511544901472649781074524520750015753401075124546192".to_string(),
-              "13265664615667652438789999049351647612638589194418028696721110024545209240245".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "2117378660950893546727814473886971659167432730539153849939512739200702503245".to_string(),
-              "12205359061008714457233733605183853461049415901081652638746589684703684898085".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "10566821862315077178806104928944101674606953837733742141460939837562283557106".to_string(),
-              "7674596940238494371146955016472389653206629774315881560174607338434178695752".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "4113246305952002410836630545320048893831804716717760502322329658392516177157".to_string(),
-              "2582495162214693865485449556707643861588432316376893482956650698880036027760".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "20256693581650511627508605728963644225029552513871906025264016420668844849954".to_string(),
-              "12046581620637748907292115969446085562219751931856730986934696177199258594367".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "16623453222175488768803877928115854216457893375289846608149341380433335390264".to_string(),
-              "20980548229192775162392942720554544442201001541140648561609184738736036115588".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "20568305659608905845211487930387411431885234200374506228290132380352892884377".to_string(),
-              "11307115280060459353713777285473062810900052439880886474317440718763946063629".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "3838648357713189065800348378040071684774469060609379455364519829505660691767".to_string(),
-              "7059951936368799551213701438908864900907168553371150826060510959256874687494".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "3507168370743824993280363915556711971116855778400710638483150695489655644035".to_string(),
-              "10450347161561586251232563671031812114693582330047082305628819792992289378888".to_string(),
-              "1".to_string(),
-              ],
-              vec![
-              "6289090165086218935848950899207186578398634671336111164113557140672876572076".to_string(),
-              "14303414163305840776877475218248221862336328016123213730298518782072837008926".to_string(),
-              "1".to_string(),
-              ],
-              ],
-              vk_alpha_1: vec![
-                "20491192805390485299153009773594534940189261866228447918068658471970481763042".to_string(),
-                "9383485363053290200918347156157836566562967994039712273449902621266178545958".to_string(),
-                "1".to_string()
-              ], 
-              vk_beta_2: vec![
-                vec![
-                "6375614351688725206403948262868962793625744043794305715222011528459656738731".to_string(),
-                "4252822878758300859123897981450591353533073413197771768651442665752259397132".to_string(),
-                ],
-                vec![
-                "10505242626370262277552901082094356697409835680220590971873171140371331206856".to_string(),
-                "21847035105528745403288232691147584728191162732299865338377159692350059136679".to_string(),
-                ],
-                vec![
-                "1".to_string(),
-                "0".to_string(),
-                ],
-                ], 
-                vk_gamma_2: vec![
-                vec![
-                "10857046999023057135944570762232829481370756359578518086990519993285655852781".to_string(),
-                "11559732032986387107991004021392285783925812861821192530917403151452391805634".to_string(),
-                ],
-                vec![
-                "8495653923123431417604973247489272438418190587263600148770280649306958101930".to_string(),
-                "4082367875863433681332203403145435568316851327593401208105741076214120093531".to_string(),
-                ],
-                vec![
-                "1".to_string(),
-                "0".to_string(),
-                ],
-                ], 
-              vk_delta_2: vec![
-                vec![
-                "1284619067782625262033299823943842199378997899590458398651143911293904840584".to_string(),
-                "15230376273229886795002338330683690771549045328259379373615801464957198430450".to_string(),
-                ],
-                vec![
-                "7967187382060100406390096977719265945347583969849404952170747602356838614509".to_string(),
-                "21657085724858073143598952611779032379828005115043041502513345600210438172407".to_string(),
-                ],
-                vec![
-                "1".to_string(),
-                "0".to_string(),
-                ],
-                ], 
-                vk_alphabeta_12: vec![
-                vec![
-                vec![
-                "2029413683389138792403550203267699914886160938906632433982220835551125967885".to_string(),
-                "21072700047562757817161031222997517981543347628379360635925549008442030252106".to_string(),
-                ],
-                vec![
-                "5940354580057074848093997050200682056184807770593307860589430076672439820312".to_string(),
-                "12156638873931618554171829126792193045421052652279363021382169897324752428276".to_string(),
-                ],
-                vec![
-                "7898200236362823042373859371574133993780991612861777490112507062703164551277".to_string(),
-                "7074218545237549455313236346927434013100842096812539264420499035217050630853".to_string(),
-                ],
-                ],
-                vec![
-                vec![
-                "7077479683546002997211712695946002074877511277312570035766170199895071832130".to_string(),
-                "10093483419865920389913245021038182291233451549023025229112148274109565435465".to_string(),
-                ],
-                vec![
-                "4595479056700221319381530156280926371456704509942304414423590385166031118820".to_string(),
-                "19831328484489333784475432780421641293929726139240675179672856274388269393268".to_string(),
-                ],
-                vec![
-                "11934129596455521040620786944827826205713621633706285934057045369193958244500".to_string(),
-                "8037395052364110730298837004334506829870972346962140206007064471173334027475".to_string(),
-                ],
-                ],
-                ], inputs_count: 65, curve: "bn128".to_string(), protocol: "groth16".to_string() },
-        }
+
+    /*
+    * @dev Encodes the prepared verifying key, including the precomputed e(alpha, beta) pairing
+    * and the negated gamma/delta line coefficients, in the canonical uncompressed encoding.
+    */
+    pub fn to_bytes(&self) -> StdResult<Vec<u8>> {
+        let pvk = &self.pvk;
+        let mut bytes = Vec::new();
+        let mut write = || -> Result<(), SerializationError> {
+            pvk.vk.serialize_uncompressed(&mut bytes)?;
+            pvk.alpha_g1_beta_g2.serialize_uncompressed(&mut bytes)?;
+            for prepared in [&pvk.gamma_g2_neg_pc, &pvk.delta_g2_neg_pc] {
+                prepared.ell_coeffs.serialize_uncompressed(&mut bytes)?;
+                prepared.infinity.serialize_uncompressed(&mut bytes)?;
+            }
+            Ok(())
+        };
+        write().map_err(|e| StdError::serialize_err("PreparedVerifyingKey", e))?;
+        Ok(bytes)
     }
-    pub fn verify_proof(self, proof: Proof<Bn254>, inputs: &[Fr]) -> bool {
 
-        let vk = self.vk_json.to_verifying_key();
-        let pvk = prepare_verifying_key(&vk);
+    /*
+    * @dev Decodes a prepared verifying key written by `to_bytes`. The bytes are only ever produced
+    * by this contract, so the curve checks are skipped.
+    */
+    pub fn from_bytes(mut bytes: &[u8]) -> StdResult<Self> {
+        let mut read = || -> Result<PreparedVerifyingKey<Bn254>, SerializationError> {
+            let vk = VerifyingKey::<Bn254>::deserialize_unchecked(&mut bytes)?;
+            let alpha_g1_beta_g2 = Fq12::deserialize_unchecked(&mut bytes)?;
+            let gamma_g2_neg_pc = G2Prepared {
+                ell_coeffs: Vec::deserialize_unchecked(&mut bytes)?,
+                infinity: bool::deserialize_unchecked(&mut bytes)?,
+            };
+            let delta_g2_neg_pc = G2Prepared {
+                ell_coeffs: Vec::deserialize_unchecked(&mut bytes)?,
+                infinity: bool::deserialize_unchecked(&mut bytes)?,
+            };
+            Ok(PreparedVerifyingKey { vk, alpha_g1_beta_g2, gamma_g2_neg_pc, delta_g2_neg_pc })
+        };
+        let pvk = read().map_err(|e| StdError::parse_err("PreparedVerifyingKey", e))?;
+        Ok(Self { pvk })
+    }
 
-        verify_proof(&pvk, &proof, inputs).unwrap()
+    pub fn verify_proof(&self, proof: Proof<Bn254>, inputs: &[Fr]) -> Result<bool, ContractError> {
+        let expected = self.pvk.vk.gamma_abc_g1.len().saturating_sub(1);
+        if inputs.len() != expected {
+            return Err(ContractError::InvalidPublicInputCount { expected, got: inputs.len() });
+        }
+        verify_proof(&self.pvk, &proof, inputs).map_err(|e| StdError::generic_err(e.to_string()).into())
     }
 }
 
@@ -546,90 +93,198 @@ pub struct VerifyingKeyJson {
 }
 
 impl VerifyingKeyJson {
-    pub fn to_verifying_key(self) -> VerifyingKey<Bn254> {
-        let alpha_g1 = G1Affine::from(G1Projective::new(
-            str_to_fq(&self.vk_alpha_1[0]),
-            str_to_fq(&self.vk_alpha_1[1]),
-            str_to_fq(&self.vk_alpha_1[2]),
-        ));
-        let beta_g2 = G2Affine::from(G2Projective::new(
-            // x
-            Fq2::new(
-                str_to_fq(&self.vk_beta_2[0][0]),
-                str_to_fq(&self.vk_beta_2[0][1]),
-            ),
-            // y
-            Fq2::new(
-                str_to_fq(&self.vk_beta_2[1][0]),
-                str_to_fq(&self.vk_beta_2[1][1]),
-            ),
-            // z,
-            Fq2::new(
-                str_to_fq(&self.vk_beta_2[2][0]),
-                str_to_fq(&self.vk_beta_2[2][1]),
-            ),
-        ));
-
-        let gamma_g2 = G2Affine::from(G2Projective::new(
-            // x
-            Fq2::new(
-                str_to_fq(&self.vk_gamma_2[0][0]),
-                str_to_fq(&self.vk_gamma_2[0][1]),
-            ),
-            // y
-            Fq2::new(
-                str_to_fq(&self.vk_gamma_2[1][0]),
-                str_to_fq(&self.vk_gamma_2[1][1]),
-            ),
-            // z,
-            Fq2::new(
-                str_to_fq(&self.vk_gamma_2[2][0]),
-                str_to_fq(&self.vk_gamma_2[2][1]),
-            ),
-        ));
-
-        let delta_g2 = G2Affine::from(G2Projective::new(
-            // x
-            Fq2::new(
-                str_to_fq(&self.vk_delta_2[0][0]),
-                str_to_fq(&self.vk_delta_2[0][1]),
-            ),
-            // y
-            Fq2::new(
-                str_to_fq(&self.vk_delta_2[1][0]),
-                str_to_fq(&self.vk_delta_2[1][1]),
-            ),
-            // z,
-            Fq2::new(
-                str_to_fq(&self.vk_delta_2[2][0]),
-                str_to_fq(&self.vk_delta_2[2][1]),
-            ),
-        ));
-
-        let gamma_abc_g1: Vec<G1Affine> = self
+    pub fn to_verifying_key(self) -> Result<VerifyingKey<Bn254>, ContractError> {
+        let alpha_g1 = projective_json_to_g1("vk_alpha_1", &self.vk_alpha_1)?;
+        let beta_g2 = projective_json_to_g2("vk_beta_2", &self.vk_beta_2)?;
+        let gamma_g2 = projective_json_to_g2("vk_gamma_2", &self.vk_gamma_2)?;
+        let delta_g2 = projective_json_to_g2("vk_delta_2", &self.vk_delta_2)?;
+
+        let gamma_abc_g1 = self
             .ic
             .iter()
-            .map(|coords| {
-                G1Affine::from(G1Projective::new(
-                    str_to_fq(&coords[0]),
-                    str_to_fq(&coords[1]),
-                    str_to_fq(&coords[2]),
-                ))
-            })
-            .collect();
-
-        VerifyingKey::<Bn254> {
+            .enumerate()
+            .map(|(i, coords)| projective_json_to_g1(&format!("IC[{}]", i), coords))
+            .collect::<Result<Vec<G1Affine>, ContractError>>()?;
+
+        Ok(VerifyingKey::<Bn254> {
             alpha_g1,
             beta_g2,
             gamma_g2,
             delta_g2,
             gamma_abc_g1,
-        }
+        })
+    }
+}
+
+/*
+* @dev Parses a decimal string into a base field element, naming the offending field on failure.
+* Values at or above the modulus are rejected instead of being silently reduced.
+*/
+pub fn str_to_fq(field: &str, s: &str) -> Result<Fq, ContractError> {
+    let repr = decimal_to_repr(s).ok_or_else(|| ContractError::InvalidFieldElement {
+        field: field.to_string(),
+        value: s.to_string(),
+    })?;
+    Fq::from_repr(repr).ok_or_else(|| ContractError::CoordinateOutOfRange {
+        field: field.to_string(),
+        value: s.to_string(),
+    })
+}
+
+/*
+* @dev Parses a decimal public signal into a scalar field element. Values at or above the scalar
+* field modulus are rejected so that no two encodings map to the same circuit input.
+*/
+pub fn str_to_fr(field: &str, s: &str) -> Result<Fr, ContractError> {
+    let repr = decimal_to_repr(s).ok_or_else(|| ContractError::InvalidFieldElement {
+        field: field.to_string(),
+        value: s.to_string(),
+    })?;
+    Fr::from_repr(repr).ok_or_else(|| ContractError::NonCanonicalPublicInput {
+        field: field.to_string(),
+        value: s.to_string(),
+    })
+}
+
+/*
+* @dev Computes the public input of the step circuit. The finalized slot, finalized header root,
+* participation, execution state root and sync committee poseidon are folded together with
+* SHA-256 (integers as 32 byte little endian), and the digest, read as a little endian integer,
+* is truncated to its low 253 bits so it always lies below the scalar field modulus.
+*/
+pub fn step_commitment(
+    finalized_slot: Uint256,
+    finalized_header_root: &[u8; 32],
+    participation: Uint256,
+    execution_state_root: &[u8; 32],
+    sync_committee_poseidon: &[u8; 32],
+) -> Fr {
+    let mut h = [0u8; 32];
+    let mut temp = [0u8; 64];
+
+    temp[..32].copy_from_slice(&finalized_slot.to_le_bytes());
+    temp[32..].copy_from_slice(finalized_header_root);
+    h.copy_from_slice(&Sha256::digest(temp));
+
+    for input in [&participation.to_le_bytes(), execution_state_root, sync_committee_poseidon] {
+        temp[..32].copy_from_slice(&h);
+        temp[32..].copy_from_slice(input);
+        h.copy_from_slice(&Sha256::digest(temp));
+    }
+
+    h[31] &= 0b0001_1111;
+    Fr::from_le_bytes_mod_order(&h)
+}
+
+/*
+* @dev Parses a decimal string into the little endian limbs of a 256 bit integer.
+*/
+pub fn decimal_to_repr(s: &str) -> Option<BigInteger256> {
+    let bytes = Uint256::from_str(s).ok()?.to_le_bytes();
+    let mut limbs = [0u64; 4];
+    for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks_exact(8)) {
+        let mut limb_bytes = [0u8; 8];
+        limb_bytes.copy_from_slice(chunk);
+        *limb = u64::from_le_bytes(limb_bytes);
+    }
+    Some(BigInteger256::new(limbs))
+}
+
+/*
+* @dev Parses an affine G1 point given as [x, y]. G1 has cofactor 1 on BN254, so every point
+* on the curve is in the prime order subgroup.
+*/
+pub fn affine_json_to_g1(field: &str, coords: &[String]) -> Result<G1Affine, ContractError> {
+    expect_coordinates(field, coords, 2)?;
+    let point = G1Affine::new(str_to_fq(field, &coords[0])?, str_to_fq(field, &coords[1])?, false);
+    check_on_curve(field, point.is_on_curve())?;
+    Ok(point)
+}
+
+/*
+* @dev Parses an affine G2 point given as [[x_c0, x_c1], [y_c0, y_c1]].
+*/
+pub fn affine_json_to_g2(field: &str, coords: &[Vec<String>]) -> Result<G2Affine, ContractError> {
+    expect_coordinates(field, coords, 2)?;
+    let point = G2Affine::new(str_to_fq2(field, &coords[0])?, str_to_fq2(field, &coords[1])?, false);
+    check_on_curve(field, point.is_on_curve())?;
+    check_in_subgroup(field, point.is_in_correct_subgroup_assuming_on_curve())?;
+    Ok(point)
+}
+
+/*
+* @dev Parses a G1 point in the Jacobian [x, y, z] form used by snarkjs verifying keys.
+*/
+fn projective_json_to_g1(field: &str, coords: &[String]) -> Result<G1Affine, ContractError> {
+    expect_coordinates(field, coords, 3)?;
+    let point = G1Affine::from(G1Projective::new(
+        str_to_fq(field, &coords[0])?,
+        str_to_fq(field, &coords[1])?,
+        str_to_fq(field, &coords[2])?,
+    ));
+    check_on_curve(field, point.is_on_curve())?;
+    Ok(point)
+}
+
+/*
+* @dev Parses a G2 point in the Jacobian [x, y, z] form used by snarkjs verifying keys.
+*/
+fn projective_json_to_g2(field: &str, coords: &[Vec<String>]) -> Result<G2Affine, ContractError> {
+    expect_coordinates(field, coords, 3)?;
+    let point = G2Affine::from(G2Projective::new(
+        str_to_fq2(field, &coords[0])?,
+        str_to_fq2(field, &coords[1])?,
+        str_to_fq2(field, &coords[2])?,
+    ));
+    check_on_curve(field, point.is_on_curve())?;
+    check_in_subgroup(field, point.is_in_correct_subgroup_assuming_on_curve())?;
+    Ok(point)
+}
+
+fn str_to_fq2(field: &str, coords: &[String]) -> Result<Fq2, ContractError> {
+    expect_coordinates(field, coords, 2)?;
+    Ok(Fq2::new(str_to_fq(field, &coords[0])?, str_to_fq(field, &coords[1])?))
+}
+
+fn expect_coordinates<T>(field: &str, coords: &[T], expected: usize) -> Result<(), ContractError> {
+    if coords.len() != expected {
+        return Err(ContractError::InvalidCoordinateCount {
+            field: field.to_string(),
+            expected,
+            got: coords.len(),
+        });
     }
+    Ok(())
 }
 
-pub fn str_to_fq(s: &str) -> Fq {
-    Fq::from_str(s).unwrap()
+fn check_on_curve(field: &str, on_curve: bool) -> Result<(), ContractError> {
+    if !on_curve {
+        return Err(ContractError::PointNotOnCurve { point: field.to_string() });
+    }
+    Ok(())
+}
+
+fn check_in_subgroup(field: &str, in_subgroup: bool) -> Result<(), ContractError> {
+    if !in_subgroup {
+        return Err(ContractError::PointNotInSubgroup { point: field.to_string() });
+    }
+    Ok(())
+}
+
+/*
+* @dev snarkjs verification keys for the Telepathy step and rotate circuits, used by the unit tests.
+*/
+#[cfg(test)]
+pub(crate) mod fixtures {
+    use super::VerifyingKeyJson;
+
+    pub fn step_verifying_key() -> VerifyingKeyJson {
+        serde_json::from_str(include_str!("../testdata/step_verification_key.json")).unwrap()
+    }
+
+    pub fn rotate_verifying_key() -> VerifyingKeyJson {
+        serde_json::from_str(include_str!("../testdata/rotate_verification_key.json")).unwrap()
+    }
 }
 
 
@@ -642,7 +297,7 @@ mod tests {
 
     #[test]
     fn test_verifier_step_simple_proof() {
-        let v = Verifier::new_step_verifier();
+        let v = Verifier::new(fixtures::step_verifying_key()).unwrap();
         let groth_16_proof = Groth16Proof {
           a: vec!["19052226342225059169368468943242899722463738230905472208500084961135663160509".to_string(), "16380864488893534373718997305335489269591160449720961122684967788310493516960".to_string()],
           b: vec![vec!["2406202055061937495864025448062673105573298015762558145337278147528693758087".to_string(), "4244962819146553706141100213693629757064153729737155348694001350554073199025".to_string()], vec!["4919212484791842246061291319810230307273866158940801673938573541010074937108".to_string(), "19863879735005091764507944581578827016309554400620309321981302697664139308420".to_string()]],
@@ -657,14 +312,14 @@ mod tests {
           curve: "bn128".to_string(),
         };
 
-        let proof = circom_proof.to_proof();
+        let proof = circom_proof.to_proof().unwrap();
         println!("After proof!");
         
         let public_signals_arr = vec!["11375407177000571624392859794121663751494860578980775481430212221322179592816".to_string()];
         let public_signals = PublicSignals::from(public_signals_arr);
         println!("After public signals!");
 
-        let res = v.verify_proof(proof, &public_signals.get());
+        let res = v.verify_proof(proof, &public_signals.get().unwrap()).unwrap();
 
         println!("res: {}", res);
         assert!(res);
@@ -672,7 +327,7 @@ mod tests {
 
     #[test]
     fn test_verifier_step_real_inputs() {
-        let v = Verifier::new_step_verifier();
+        let v = Verifier::new(fixtures::step_verifying_key()).unwrap();
         let groth_16_proof = Groth16Proof {
           a: vec!["14717729948616455402271823418418032272798439132063966868750456734930753033999".to_string(), "10284862272179454279380723177303354589165265724768792869172425850641532396958".to_string()],
           b: vec![vec!["11269943315518713067124801671029240901063146909738584854987772776806315890545".to_string(), "20094085308485991030092338753416508135313449543456147939097124612984047201335".to_string()], vec!["8122139689435793554974799663854817979475528090524378333920791336987132768041".to_string(), "5111528818556913201486596055325815760919897402988418362773344272232635103877".to_string()]],
@@ -687,7 +342,7 @@ mod tests {
           curve: "bn128".to_string(),
         };
 
-        let proof = circom_proof.to_proof();
+        let proof = circom_proof.to_proof().unwrap();
 
         println!("After proof!");
         println!("proof: {:?}", proof);
@@ -695,14 +350,14 @@ mod tests {
         let public_signals = PublicSignals::from(public_signals_arr);
         println!("After public signals!");
 
-        let res = v.verify_proof(proof, &public_signals.get());
+        let res = v.verify_proof(proof, &public_signals.get().unwrap()).unwrap();
 
         println!("res: {}", res);
         assert!(res);
     }
     #[test]
     fn test_verifier_rotate() {
-        let v = Verifier::new_rotate_verifier();
+        let v = Verifier::new(fixtures::rotate_verifying_key()).unwrap();
 
         let circom_proof: CircomProof = CircomProof{
             pi_a : vec!["19432175986645681540999611667567820365521443728844489852797484819167568900221".to_string(), "17819747348018194504213652705429154717568216715442697677977860358267208774881".to_string()],
@@ -713,7 +368,7 @@ mod tests {
         };
         
 
-        let proof = circom_proof.to_proof();
+        let proof = circom_proof.to_proof().unwrap();
         println!("After proof!");
 
         let public_signals_vec = vec!["193".to_string(),
@@ -786,9 +441,136 @@ mod tests {
         let public_signals = PublicSignals::from(public_signals_vec);
         println!("After public signals!");
 
-        let res = v.verify_proof(proof, &public_signals.get());
+        let res = v.verify_proof(proof, &public_signals.get().unwrap()).unwrap();
 
         println!("res: {}", res);
         assert!(res);
     }
+
+    #[test]
+    fn test_prepared_verifying_key_round_trip() {
+        let v = Verifier::new(fixtures::rotate_verifying_key()).unwrap();
+        let bytes = v.to_bytes().unwrap();
+        let decoded = Verifier::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.pvk, v.pvk);
+
+        // Truncated encodings are rejected rather than partially decoded
+        assert!(Verifier::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_malformed_proof_is_rejected() {
+        let circom_proof = CircomProof {
+            pi_a: vec!["1".to_string(), "3".to_string()],
+            pi_b: vec![vec!["0".to_string(), "0".to_string()], vec!["0".to_string(), "0".to_string()]],
+            pi_c: vec!["1".to_string(), "2".to_string()],
+            protocol: "groth16".to_string(),
+            curve: "bn128".to_string(),
+        };
+        let err = circom_proof.clone().to_proof().unwrap_err();
+        assert!(matches!(err, ContractError::PointNotOnCurve { point } if point == "pi_a"));
+
+        let mut bad_field = circom_proof.clone();
+        bad_field.pi_a = vec!["0x01".to_string(), "2".to_string()];
+        let err = bad_field.to_proof().unwrap_err();
+        assert!(matches!(err, ContractError::InvalidFieldElement { field, .. } if field == "pi_a"));
+
+        let mut missing_coordinate = circom_proof;
+        missing_coordinate.pi_a = vec!["1".to_string()];
+        let err = missing_coordinate.to_proof().unwrap_err();
+        assert!(matches!(err, ContractError::InvalidCoordinateCount { expected: 2, got: 1, .. }));
+
+        let err = PublicSignals::from(vec!["1".to_string(), "-1".to_string()]).get().unwrap_err();
+        assert!(matches!(err, ContractError::InvalidFieldElement { field, .. } if field == "public_signals[1]"));
+    }
+
+    #[test]
+    fn test_invalid_proof_points_are_rejected() {
+        let valid_b = vec![
+            vec!["11269943315518713067124801671029240901063146909738584854987772776806315890545".to_string(), "20094085308485991030092338753416508135313449543456147939097124612984047201335".to_string()],
+            vec!["8122139689435793554974799663854817979475528090524378333920791336987132768041".to_string(), "5111528818556913201486596055325815760919897402988418362773344272232635103877".to_string()],
+        ];
+        let proof = CircomProof {
+            pi_a: vec!["1".to_string(), "2".to_string()],
+            pi_b: valid_b,
+            pi_c: vec!["1".to_string(), "2".to_string()],
+            protocol: "groth16".to_string(),
+            curve: "bn128".to_string(),
+        };
+        assert!(proof.clone().to_proof().is_ok());
+
+        // p + 1 reduces to the generator's x coordinate but must not be accepted
+        let mut out_of_range = proof.clone();
+        out_of_range.pi_a = vec!["21888242871839275222246405745257275088696311157297823662689037894645226208584".to_string(), "2".to_string()];
+        let err = out_of_range.to_proof().unwrap_err();
+        assert!(matches!(err, ContractError::CoordinateOutOfRange { field, .. } if field == "pi_a"));
+
+        let mut off_curve = proof.clone();
+        off_curve.pi_c = vec!["1".to_string(), "3".to_string()];
+        let err = off_curve.to_proof().unwrap_err();
+        assert!(matches!(err, ContractError::PointNotOnCurve { point } if point == "pi_c"));
+
+        // On the twist y^2 = x^3 + b', but outside the order r subgroup
+        let mut wrong_subgroup = proof;
+        wrong_subgroup.pi_b = vec![
+            vec!["1".to_string(), "0".to_string()],
+            vec!["18278151005453108793778860132295291098363647455926340152056652516292830556603".to_string(), "5912654199736721486680175016176231956195085055698687135131307249486702594212".to_string()],
+        ];
+        let err = wrong_subgroup.to_proof().unwrap_err();
+        assert!(matches!(err, ContractError::PointNotInSubgroup { point } if point == "pi_b"));
+    }
+
+    #[test]
+    fn test_public_input_count_mismatch() {
+        let v = Verifier::new(fixtures::step_verifying_key()).unwrap();
+        let proof = CircomProof {
+            pi_a: vec!["1".to_string(), "2".to_string()],
+            pi_b: vec![
+                vec!["10857046999023057135944570762232829481370756359578518086990519993285655852781".to_string(), "11559732032986387107991004021392285783925812861821192530917403151452391805634".to_string()],
+                vec!["8495653923123431417604973247489272438418190587263600148770280649306958101930".to_string(), "4082367875863433681332203403145435568316851327593401208105741076214120093531".to_string()],
+            ],
+            pi_c: vec!["1".to_string(), "2".to_string()],
+            protocol: "groth16".to_string(),
+            curve: "bn128".to_string(),
+        }.to_proof().unwrap();
+        let err = v.verify_proof(proof, &[]).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidPublicInputCount { expected: 1, got: 0 }));
+    }
+
+    #[test]
+    fn test_step_commitment() {
+        // Inputs of testStep() in LightClient.t.sol, matching the public signal of test_verifier_step_real_inputs
+        let finalized_header_root: [u8; 32] = hex::decode("70d0a7f53a459dd88eb37c6cfdfb8c48f120e504c96b182357498f2691aa5653").unwrap().try_into().unwrap();
+        let execution_state_root: [u8; 32] = hex::decode("69d746cb81cd1fb4c11f4dcc04b6114596859b518614da0dd3b4192ff66c3a58").unwrap().try_into().unwrap();
+        let sync_committee_poseidon = Uint256::from_str("7032059424740925146199071046477651269705772793323287102921912953216115444414").unwrap().to_le_bytes();
+
+        let commitment = step_commitment(
+            Uint256::from(4359840u64),
+            &finalized_header_root,
+            Uint256::from(432u64),
+            &execution_state_root,
+            &sync_committee_poseidon,
+        );
+        assert_eq!(commitment, str_to_fr("commitment", "5609329213532035720595183383300565478811126222945415318009356105711007337146").unwrap());
+
+        // The truncation keeps the commitment below 2^253 for any digest
+        let commitment = step_commitment(Uint256::MAX, &[0xff; 32], Uint256::MAX, &[0xff; 32], &[0xff; 32]);
+        assert!(commitment.into_repr() < decimal_to_repr("14474011154664524427946373126085988481658748083205070504932198000989141204992").unwrap());
+    }
+
+    #[test]
+    fn test_public_signals_must_be_canonical() {
+        let r_minus_one = "21888242871839275222246405745257275088548364400416034343698204186575808495616";
+        let r = "21888242871839275222246405745257275088548364400416034343698204186575808495617";
+        let r_plus_one = "21888242871839275222246405745257275088548364400416034343698204186575808495618";
+
+        assert!(PublicSignals::from(vec![r_minus_one.to_string()]).get().is_ok());
+        for value in [r, r_plus_one] {
+            let err = PublicSignals::from(vec!["0".to_string(), value.to_string()]).get().unwrap_err();
+            assert!(matches!(err, ContractError::NonCanonicalPublicInput { field, .. } if field == "public_signals[1]"));
+        }
+
+        let err = str_to_fr("input", "1e3").unwrap_err();
+        assert!(matches!(err, ContractError::InvalidFieldElement { .. }));
+    }
 }
@@ -1,7 +1,10 @@
 pub mod contract;
 mod error;
+pub mod events;
 pub mod msg;
 pub mod state;
 pub mod helpers;
+pub mod migrations;
+pub mod ssz;
 
 pub use crate::error::ContractError;
@@ -0,0 +1,224 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{DepsMut, Env, Order, StdError, StdResult, Storage, Uint256};
+use cw_storage_plus::{Item, Map};
+use semver::Version;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::contract::{set_verifying_key, to_key};
+use crate::error::ContractError;
+use crate::msg::{Circuit, MigrateMsg};
+use crate::state::{LightClientRotate, State, BEST_UPDATES, EXECUTION_STATE_ROOTS, HEADERS, STATE, SYNC_COMMITTEE_POSEIDONS};
+
+/*
+* @dev A storage layout change. `version` is the first contract version that expects the new
+* layout; the step runs when migrating from any version below it.
+*/
+pub struct Migration {
+    pub version: &'static str,
+    pub migrate: fn(DepsMut, &Env, &MigrateMsg) -> Result<(), ContractError>,
+}
+
+/*
+* @dev Every migration step ever shipped, ordered by version. Steps are never removed so that
+* any deployed version can be brought up to date in one migration.
+*/
+pub const MIGRATIONS: &[Migration] = &[
+    Migration { version: "0.2.0", migrate: migrate_0_1_0 },
+];
+
+// `State` as stored by 0.1.0, the only layout shipped before the current one
+#[cw_serde]
+struct StateV0_1 {
+    consistent: bool,
+    head: Uint256,
+    genesis_validators_root: Vec<u8>,
+    genesis_time: Uint256,
+    seconds_per_slot: Uint256,
+    slots_per_period: Uint256,
+}
+
+/*
+* @dev Runs the migration steps needed to go from `from` to `to`, in order. Returns the
+* versions that were applied.
+*/
+pub fn run_migrations(
+    mut deps: DepsMut,
+    env: &Env,
+    msg: &MigrateMsg,
+    from: &Version,
+    to: &Version,
+    migrations: &[Migration],
+) -> Result<Vec<&'static str>, ContractError> {
+    if from > to {
+        return Err(ContractError::CannotDowngrade { from: from.to_string(), to: to.to_string() });
+    }
+
+    let mut applied = Vec::new();
+    for migration in migrations {
+        let version = parse_version(migration.version)?;
+        if from < &version && &version <= to {
+            (migration.migrate)(deps.branch(), env, msg)?;
+            applied.push(migration.version);
+        }
+    }
+    Ok(applied)
+}
+
+pub fn parse_version(version: &str) -> StdResult<Version> {
+    Version::parse(version).map_err(|e| StdError::parse_err("Version", e))
+}
+
+/*
+* @dev 0.2.0: brings a 0.1.0 deployment to the current layout in one step. 0.1.0 had its
+* verifying keys compiled in and no owner, both are taken from the migrate message and the keys
+* become version 1 of their circuit's history. The light client maps are rekeyed by number, the
+* head execution state root is read back from the stored roots and settings introduced since
+* start out at their instantiate defaults.
+*/
+fn migrate_0_1_0(mut deps: DepsMut, env: &Env, msg: &MigrateMsg) -> Result<(), ContractError> {
+    const LEGACY_STATE: Item<StateV0_1> = Item::new("state");
+    let required = |field: &str| ContractError::MissingMigrateField { field: field.to_string() };
+
+    let owner = msg.owner.as_ref().ok_or_else(|| required("owner"))?;
+    let step_verifying_key = msg.step_verifying_key.clone().ok_or_else(|| required("step_verifying_key"))?;
+    let rotate_verifying_key = msg.rotate_verifying_key.clone().ok_or_else(|| required("rotate_verifying_key"))?;
+
+    let legacy = LEGACY_STATE.load(deps.storage)?;
+    rekey_light_client_maps(deps.storage)?;
+    let head_execution_state_root = EXECUTION_STATE_ROOTS.may_load(deps.storage, to_key(legacy.head)?)?.unwrap_or_else(|| vec![0; 32]);
+    STATE.save(deps.storage, &State {
+        consistent: legacy.consistent,
+        head: legacy.head,
+        head_execution_state_root,
+        max_head_lag: None,
+        max_future_slots: Uint256::zero(),
+        capella_fork_slot: None,
+        genesis_validators_root: legacy.genesis_validators_root,
+        genesis_time: legacy.genesis_time,
+        seconds_per_slot: legacy.seconds_per_slot,
+        slots_per_period: legacy.slots_per_period,
+        owner: Some(deps.api.addr_validate(owner)?),
+        pending_owner: None,
+    })?;
+    set_verifying_key(deps.branch(), env.block.height, &Circuit::Step, step_verifying_key)?;
+    set_verifying_key(deps.branch(), env.block.height, &Circuit::Rotate, rotate_verifying_key)?;
+    Ok(())
+}
+
+/*
+* @dev The 0.1.0 light client maps were keyed by the decimal string of the slot or period,
+* they are now keyed by the big endian u64 under the same namespace.
+*/
+fn rekey_light_client_maps(storage: &mut dyn Storage) -> StdResult<()> {
+    const LEGACY_HEADERS: Map<String, Vec<u8>> = Map::new("headers");
+    const LEGACY_EXECUTION_STATE_ROOTS: Map<String, Vec<u8>> = Map::new("execution_state_roots");
+    const LEGACY_SYNC_COMMITTEE_POSEIDONS: Map<String, Vec<u8>> = Map::new("sync_committee_poseidons");
+    const LEGACY_BEST_UPDATES: Map<String, LightClientRotate> = Map::new("best_updates");
+
+    rekey(storage, LEGACY_HEADERS, HEADERS)?;
+    rekey(storage, LEGACY_EXECUTION_STATE_ROOTS, EXECUTION_STATE_ROOTS)?;
+    rekey(storage, LEGACY_SYNC_COMMITTEE_POSEIDONS, SYNC_COMMITTEE_POSEIDONS)?;
+    rekey(storage, LEGACY_BEST_UPDATES, BEST_UPDATES)
+}
+
+fn rekey<T: Serialize + DeserializeOwned>(
+    storage: &mut dyn Storage,
+    legacy: Map<String, T>,
+    map: Map<u64, T>,
+) -> StdResult<()> {
+    // Collect first, the new keys live in the same namespace as the ones being iterated
+    let entries = legacy
+        .range_raw(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<(Vec<u8>, T)>>>()?;
+
+    for (key, value) in entries {
+        let key = String::from_utf8(key)?;
+        let numeric = key.parse::<u64>().map_err(|e| StdError::parse_err("u64", e))?;
+        legacy.remove(storage, key);
+        map.save(storage, numeric, &value)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env};
+
+    fn mark_0_2(deps: DepsMut, _env: &Env, _msg: &MigrateMsg) -> Result<(), ContractError> {
+        deps.storage.set(b"0.2.0", b"1");
+        Ok(())
+    }
+
+    fn mark_0_3(deps: DepsMut, _env: &Env, _msg: &MigrateMsg) -> Result<(), ContractError> {
+        // Must observe the layout written by the previous step
+        if deps.storage.get(b"0.2.0").is_none() && deps.storage.get(b"skipped_0.2.0").is_none() {
+            return Err(StdError::generic_err("0.2.0 migration did not run first").into());
+        }
+        deps.storage.set(b"0.3.0", b"1");
+        Ok(())
+    }
+
+    const TEST_MIGRATIONS: &[Migration] = &[
+        Migration { version: "0.2.0", migrate: mark_0_2 },
+        Migration { version: "0.3.0", migrate: mark_0_3 },
+    ];
+
+    #[test]
+    fn runs_pending_migrations_in_order() {
+        let mut deps = mock_dependencies();
+        let from = parse_version("0.1.0").unwrap();
+        let to = parse_version("0.3.0").unwrap();
+
+        let applied = run_migrations(deps.as_mut(), &mock_env(), &MigrateMsg::default(), &from, &to, TEST_MIGRATIONS).unwrap();
+        assert_eq!(applied, vec!["0.2.0", "0.3.0"]);
+    }
+
+    #[test]
+    fn skips_migrations_already_applied() {
+        let mut deps = mock_dependencies();
+        deps.storage.set(b"skipped_0.2.0", b"1");
+        let from = parse_version("0.2.0").unwrap();
+        let to = parse_version("0.3.1").unwrap();
+
+        let applied = run_migrations(deps.as_mut(), &mock_env(), &MigrateMsg::default(), &from, &to, TEST_MIGRATIONS).unwrap();
+        assert_eq!(applied, vec!["0.3.0"]);
+        assert!(deps.storage.get(b"0.2.0").is_none());
+    }
+
+    #[test]
+    fn rekeys_light_client_maps() {
+        const LEGACY_HEADERS: Map<String, Vec<u8>> = Map::new("headers");
+        const LEGACY_SYNC_COMMITTEE_POSEIDONS: Map<String, Vec<u8>> = Map::new("sync_committee_poseidons");
+
+        let mut deps = mock_dependencies();
+        for slot in [9u64, 10, 4359840] {
+            LEGACY_HEADERS.save(deps.as_mut().storage, slot.to_string(), &vec![slot as u8; 32]).unwrap();
+        }
+        LEGACY_SYNC_COMMITTEE_POSEIDONS.save(deps.as_mut().storage, "532".to_string(), &vec![1; 32]).unwrap();
+
+        rekey_light_client_maps(deps.as_mut().storage).unwrap();
+
+        // Keys now sort numerically rather than lexicographically
+        let slots = HEADERS
+            .keys(deps.as_ref().storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<u64>>>()
+            .unwrap();
+        assert_eq!(slots, vec![9, 10, 4359840]);
+        assert_eq!(HEADERS.load(deps.as_ref().storage, 4359840).unwrap(), vec![4359840u64 as u8; 32]);
+        assert_eq!(SYNC_COMMITTEE_POSEIDONS.load(deps.as_ref().storage, 532).unwrap(), vec![1; 32]);
+        assert!(LEGACY_HEADERS.may_load(deps.as_ref().storage, "9".to_string()).unwrap().is_none());
+        assert_eq!(EXECUTION_STATE_ROOTS.keys(deps.as_ref().storage, None, None, Order::Ascending).count(), 0);
+    }
+
+    #[test]
+    fn refuses_downgrade() {
+        let mut deps = mock_dependencies();
+        let from = parse_version("0.3.0").unwrap();
+        let to = parse_version("0.2.0").unwrap();
+
+        let err = run_migrations(deps.as_mut(), &mock_env(), &MigrateMsg::default(), &from, &to, TEST_MIGRATIONS).unwrap_err();
+        assert!(matches!(err, ContractError::CannotDowngrade { .. }));
+    }
+}
@@ -1,5 +1,8 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Uint256};
+use cosmwasm_std::{Addr, Coin, Order, Uint128, Uint256};
+
+use crate::helpers::VerifyingKeyJson;
+use crate::state::{InconsistencyEvidence, RewardConfig};
 
 /// Message type for `instantiate` entry_point
 #[cw_serde]
@@ -10,10 +13,40 @@ pub struct InstantiateMsg {
     pub slots_per_period: u32,
     pub sync_committee_period: u32,
     pub sync_committee_poseidon: String,
+    /// snarkjs `verification_key.json` for the step circuit
+    pub step_verifying_key: VerifyingKeyJson,
+    /// snarkjs `verification_key.json` for the rotate circuit
+    pub rotate_verifying_key: VerifyingKeyJson,
+    /// Owner allowed to perform privileged operations, defaults to the instantiator
+    pub owner: Option<String>,
+    /// Steps finalizing a slot more than this many slots behind the head are rejected
+    pub max_head_lag: Option<u32>,
+    /// Clock drift tolerance, in slots a Step may finalize ahead of the current slot. Defaults to 0
+    pub max_future_slots: Option<u32>,
+    /// First slot of the Capella fork, required to prove headers more than 8192 slots older
+    /// than a stored one through the historical summaries
+    pub capella_fork_slot: Option<u32>,
+}
+
+/// Telepathy circuits whose proofs are verified by this contract
+#[cw_serde]
+pub enum Circuit {
+    Step,
+    Rotate,
+}
+
+impl Circuit {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Circuit::Step => "step",
+            Circuit::Rotate => "rotate",
+        }
+    }
 }
 
 /// Message type for 'execute' entry_point
 #[cw_serde]
+#[allow(clippy::large_enum_variant)]
 pub enum ExecuteMsg {
     Step {
         finalized_slot: u32,
@@ -40,11 +73,75 @@ pub enum ExecuteMsg {
         rotate_proof_c: [String; 2],
     },
     Force {period: u32},
+    /// Owner only. Unfreezes the light client after a detected fork by re-anchoring it on a
+    /// trusted sync committee and head, optionally dropping everything verified after them.
+    /// Refused while the light client is not frozen, and a head behind the current one
+    /// requires `prune`.
+    Recover {
+        period: u32,
+        sync_committee_poseidon: String,
+        head_slot: u32,
+        header_root: String,
+        execution_state_root: String,
+        /// Removes headers and execution state roots after `head_slot`, sync committees after
+        /// `period` and best updates from `period` on
+        prune: Option<bool>,
+    },
+    /// Owner only. Halts or resumes Step, Rotate and Force, queries keep working
+    SetPaused { paused: bool },
+    /// Owner only. Offers ownership to `new_owner`, who has to accept it
+    ProposeOwner { new_owner: String },
+    /// Completes a pending ownership transfer, sent by the proposed owner
+    AcceptOwner {},
+    /// Owner only. Leaves the contract without an owner, disabling every privileged operation
+    RenounceOwner {},
+    /// Owner only. Restricts Step and Rotate to allowlisted relayers while enabled
+    SetRelayerAllowlist { enabled: bool },
+    /// Owner only
+    AddRelayer { relayer: String },
+    /// Owner only
+    RemoveRelayer { relayer: String },
+    /// Owner only. Sets the reward paid per Step advancing the head and per Rotate
+    /// setting a new sync committee, a zero amount disables that reward
+    SetRewards { denom: String, step_reward: Uint128, rotate_reward: Uint128 },
+    /// Tops up the reward pool with the attached coins of the reward denom
+    FundRewards {},
+    /// Owner only. Sets or clears how far behind the head a Step may finalize
+    SetMaxHeadLag { max_head_lag: Option<u32> },
+    /// Owner only. Sets the clock drift tolerance, in slots a Step may finalize ahead of the current slot
+    SetMaxFutureSlots { max_future_slots: u32 },
+    /// Owner only. Sets or clears the first Capella slot, which ProveHistoricalHeader needs to
+    /// reach past `state.block_roots`
+    SetCapellaForkSlot { capella_fork_slot: Option<u32> },
+    /// Proves the block root of an older slot against the stored header of `slot` and stores
+    /// it apart from the verified headers, through `state.block_roots` within 8192 slots or
+    /// `state.historical_summaries` before that. A skipped `target_slot` resolves to the root of
+    /// the latest block before it.
+    ProveHistoricalHeader {
+        slot: u64,
+        target_slot: u64,
+        target_root: String,
+        /// Merkle branch from `target_root` up to the header root of `slot`
+        branch: Vec<String>,
+    },
+    UpdateVerifyingKey {
+        circuit: Circuit,
+        vk: VerifyingKeyJson,
+    },
 }
 
 /// Message type for `migrate` entry_point
 #[cw_serde]
-pub enum MigrateMsg {}
+#[derive(Default)]
+pub struct MigrateMsg {
+    /// Owner to set when migrating from 0.1.0, which had none
+    pub owner: Option<String>,
+    /// snarkjs `verification_key.json` for the step circuit, required when migrating from
+    /// 0.1.0, which had its keys compiled in
+    pub step_verifying_key: Option<VerifyingKeyJson>,
+    /// snarkjs `verification_key.json` for the rotate circuit, required when migrating from 0.1.0
+    pub rotate_verifying_key: Option<VerifyingKeyJson>,
+}
 
 /// Message type for `query` entry_point
 #[cw_serde]
@@ -63,6 +160,66 @@ pub enum QueryMsg {
     // GetSyncCommitteePeriodResponse gets the current slot
     #[returns(GetCurrentSlotResponse)]
     GetCurrentSlot{},
+    // VerifyingKeyHistory gets every verifying key version used for a circuit
+    #[returns(VerifyingKeyHistoryResponse)]
+    VerifyingKeyHistory {circuit: Circuit},
+    // Header gets the verified beacon block header root for a slot
+    #[returns(HeaderResponse)]
+    Header {slot: u64},
+    // BlockRoot gets the verified header root for a slot, or else the block root proven for it
+    // with ProveHistoricalHeader, which for a skipped slot is the one of the latest block before it
+    #[returns(HeaderResponse)]
+    BlockRoot {slot: u64},
+    // ExecutionStateRoot gets the verified execution state root for a slot
+    #[returns(ExecutionStateRootResponse)]
+    ExecutionStateRoot {slot: u64},
+    // SyncCommitteePoseidon gets the sync committee poseidon commitment for a period
+    #[returns(SyncCommitteePoseidonResponse)]
+    SyncCommitteePoseidon {period: u64},
+    // BestUpdate gets the best optimistic rotate update recorded for a period
+    #[returns(BestUpdateResponse)]
+    BestUpdate {period: u64},
+    // State gets the light client configuration and head, not served once the light client is
+    // frozen like the roots; InconsistencyEvidence stays available
+    #[returns(StateResponse)]
+    State {},
+    // ListHeaders pages through verified header roots by slot
+    #[returns(ListHeadersResponse)]
+    ListHeaders {start_after: Option<u64>, limit: Option<u32>, order: Option<OrderBy>},
+    // ListExecutionStateRoots pages through verified execution state roots by slot
+    #[returns(ListExecutionStateRootsResponse)]
+    ListExecutionStateRoots {start_after: Option<u64>, limit: Option<u32>, order: Option<OrderBy>},
+    // InconsistencyEvidence gets the conflicting roots that froze the light client
+    #[returns(InconsistencyEvidenceResponse)]
+    InconsistencyEvidence {},
+    // ListRelayers pages through the relayer allowlist and whether it is enforced
+    #[returns(ListRelayersResponse)]
+    ListRelayers {start_after: Option<String>, limit: Option<u32>},
+    // Rewards gets the relayer reward configuration and the remaining pool
+    #[returns(RewardsResponse)]
+    Rewards {},
+    // ForceEligibility reports whether Force can be called for the period and from which slot on
+    #[returns(ForceEligibilityResponse)]
+    ForceEligibility {period: u64},
+    // Owner gets the current and pending owner
+    #[returns(OwnerResponse)]
+    Owner {},
+}
+
+/// Iteration order for list queries, ascending by default
+#[cw_serde]
+pub enum OrderBy {
+    Ascending,
+    Descending,
+}
+
+impl From<OrderBy> for Order {
+    fn from(order: OrderBy) -> Self {
+        match order {
+            OrderBy::Ascending => Order::Ascending,
+            OrderBy::Descending => Order::Descending,
+        }
+    }
 }
 
 // We define a custom struct for each query response
@@ -75,3 +232,106 @@ pub struct GetSyncCommitteePeriodResponse {
 pub struct GetCurrentSlotResponse {
     pub slot: Uint256
 }
+
+#[cw_serde]
+pub struct VerifyingKeyVersionResponse {
+    pub version: u32,
+    pub activated_at_height: u64,
+    pub vk: VerifyingKeyJson,
+}
+
+#[cw_serde]
+pub struct VerifyingKeyHistoryResponse {
+    pub versions: Vec<VerifyingKeyVersionResponse>,
+}
+
+#[cw_serde]
+pub struct HeaderResponse {
+    pub slot: u64,
+    /// Hex encoded beacon block header root
+    pub root: String,
+}
+
+#[cw_serde]
+pub struct ExecutionStateRootResponse {
+    pub slot: u64,
+    /// Hex encoded execution state root
+    pub root: String,
+}
+
+#[cw_serde]
+pub struct SyncCommitteePoseidonResponse {
+    pub period: u64,
+    pub poseidon: Uint256,
+}
+
+#[cw_serde]
+pub struct BestUpdateResponse {
+    pub period: u64,
+    pub finalized_slot: Uint256,
+    pub participation: Uint256,
+    pub finalized_header_root: String,
+    pub execution_state_root: String,
+    pub sync_committee_ssz: String,
+    pub sync_committee_poseidon: Uint256,
+}
+
+#[cw_serde]
+pub struct StateResponse {
+    pub consistent: bool,
+    pub head: Uint256,
+    pub head_execution_state_root: String,
+    pub max_head_lag: Option<Uint256>,
+    pub max_future_slots: Uint256,
+    pub capella_fork_slot: Option<Uint256>,
+    pub genesis_validators_root: String,
+    pub genesis_time: Uint256,
+    pub seconds_per_slot: Uint256,
+    pub slots_per_period: Uint256,
+    pub owner: Option<Addr>,
+    pub paused: bool,
+}
+
+#[cw_serde]
+pub struct ListHeadersResponse {
+    pub headers: Vec<HeaderResponse>,
+}
+
+#[cw_serde]
+pub struct ListExecutionStateRootsResponse {
+    pub roots: Vec<ExecutionStateRootResponse>,
+}
+
+#[cw_serde]
+pub struct InconsistencyEvidenceResponse {
+    pub evidence: Vec<InconsistencyEvidence>,
+}
+
+#[cw_serde]
+pub struct OwnerResponse {
+    pub owner: Option<Addr>,
+    pub pending_owner: Option<Addr>,
+}
+
+#[cw_serde]
+pub struct ListRelayersResponse {
+    pub enabled: bool,
+    pub relayers: Vec<Addr>,
+}
+
+#[cw_serde]
+pub struct RewardsResponse {
+    pub config: Option<RewardConfig>,
+    pub pool: Option<Coin>,
+}
+
+#[cw_serde]
+pub struct ForceEligibilityResponse {
+    pub period: u64,
+    pub best_update_exists: bool,
+    pub participation: Option<Uint256>,
+    pub next_sync_committee_initialized: bool,
+    // Force is refused before this slot, the first one of the next period
+    pub earliest_force_slot: Uint256,
+    pub eligible: bool,
+}
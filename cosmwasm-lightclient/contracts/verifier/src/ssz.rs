@@ -0,0 +1,268 @@
+use sha2::{Digest, Sha256};
+
+use crate::error::ContractError;
+use crate::state::BeaconBlockHeader;
+
+/*
+* Port of `SimpleSerialize.sol`: SSZ merkleization of the containers the light client
+* handles, Merkle branches by generalized index, and signing domains.
+*/
+
+// Generalized indices of the light client proofs in the BeaconState and BeaconBlockBody
+pub const FINALIZED_ROOT_INDEX: u64 = 105;
+pub const NEXT_SYNC_COMMITTEE_INDEX: u64 = 55;
+pub const EXECUTION_STATE_ROOT_INDEX: u64 = 402;
+// Generalized index of the state root in a BeaconBlockHeader
+pub const HEADER_STATE_ROOT_INDEX: u64 = 11;
+
+pub const DOMAIN_SYNC_COMMITTEE: [u8; 4] = [7, 0, 0, 0];
+
+pub const SLOTS_PER_HISTORICAL_ROOT: u64 = 8192;
+// Generalized indices of `block_roots` and `historical_summaries` in a Capella BeaconState
+pub const BLOCK_ROOTS_INDEX: u64 = 37;
+pub const HISTORICAL_SUMMARIES_INDEX: u64 = 59;
+// log2(HISTORICAL_ROOTS_LIMIT), the depth of the historical summaries list
+const HISTORICAL_SUMMARIES_DEPTH: u32 = 24;
+
+pub fn sha256_pair(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+/*
+* @dev Merkleizes chunks into a balanced tree padded with zero chunks to a power of two.
+*/
+fn merkleize(chunks: &[[u8; 32]]) -> [u8; 32] {
+    let mut layer = chunks.to_vec();
+    layer.resize(chunks.len().next_power_of_two().max(1), [0; 32]);
+    while layer.len() > 1 {
+        layer = layer.chunks(2).map(|pair| sha256_pair(&pair[0], &pair[1])).collect();
+    }
+    layer[0]
+}
+
+fn uint64_chunk(value: u64) -> [u8; 32] {
+    let mut chunk = [0u8; 32];
+    chunk[..8].copy_from_slice(&value.to_le_bytes());
+    chunk
+}
+
+fn root_chunk(field: &str, root: &[u8]) -> Result<[u8; 32], ContractError> {
+    root.try_into().map_err(|_| ContractError::InvalidRootLength {
+        field: field.to_string(),
+        expected: 32,
+        got: root.len(),
+    })
+}
+
+impl BeaconBlockHeader {
+    /*
+    * @dev Mirrors `SSZ.sszBeaconBlockHeader`.
+    */
+    pub fn hash_tree_root(&self) -> Result<[u8; 32], ContractError> {
+        Ok(merkleize(&[
+            uint64_chunk(self.slot),
+            uint64_chunk(self.proposer_index),
+            root_chunk("parent_root", &self.parent_root)?,
+            root_chunk("state_root", &self.state_root)?,
+            root_chunk("body_root", &self.body_root)?,
+        ]))
+    }
+}
+
+/*
+* @dev Depth of a generalized index, i.e. the length of its Merkle branch.
+*/
+pub fn gindex_depth(index: u64) -> usize {
+    (u64::BITS - 1 - index.leading_zeros()) as usize
+}
+
+/*
+* @dev Generalized index of `inner`, taken relative to the node at `outer`.
+*/
+pub fn concat_gindex(outer: u64, inner: u64) -> u64 {
+    let depth = gindex_depth(inner);
+    (outer << depth) | (inner - (1 << depth))
+}
+
+/*
+* @dev Generalized index of `state.block_roots[slot % SLOTS_PER_HISTORICAL_ROOT]`.
+*/
+pub fn block_roots_index(slot: u64) -> u64 {
+    BLOCK_ROOTS_INDEX * SLOTS_PER_HISTORICAL_ROOT + slot % SLOTS_PER_HISTORICAL_ROOT
+}
+
+/*
+* @dev Generalized index of the block root of `slot` under
+* `state.historical_summaries[summary_index].block_summary_root`. The list elements sit under the
+* left child of the field, the right one is the length mix in.
+*/
+pub fn historical_block_root_index(summary_index: u64, slot: u64) -> u64 {
+    let summary = ((HISTORICAL_SUMMARIES_INDEX * 2) << HISTORICAL_SUMMARIES_DEPTH) | summary_index;
+    concat_gindex(summary * 2, SLOTS_PER_HISTORICAL_ROOT + slot % SLOTS_PER_HISTORICAL_ROOT)
+}
+
+pub fn restore_merkle_root(leaf: &[u8; 32], index: u64, branch: &[[u8; 32]]) -> [u8; 32] {
+    branch.iter().enumerate().fold(*leaf, |value, (i, sibling)| {
+        match (index >> i) & 1 {
+            1 => sha256_pair(sibling, &value),
+            _ => sha256_pair(&value, sibling),
+        }
+    })
+}
+
+/*
+* @dev Unlike `SimpleSerialize.sol`, the branch must also be exactly as deep as the index.
+*/
+pub fn is_valid_merkle_branch(leaf: &[u8; 32], index: u64, branch: &[[u8; 32]], root: &[u8; 32]) -> bool {
+    index != 0 && branch.len() == gindex_depth(index) && restore_merkle_root(leaf, index, branch) == *root
+}
+
+/*
+* @dev hash_tree_root of `ForkData(current_version, genesis_validators_root)`.
+*/
+pub fn compute_fork_data_root(fork_version: [u8; 4], genesis_validators_root: &[u8; 32]) -> [u8; 32] {
+    let mut version = [0u8; 32];
+    version[..4].copy_from_slice(&fork_version);
+    sha256_pair(&version, genesis_validators_root)
+}
+
+/*
+* @dev The domain type followed by the first 28 bytes of the fork data root, see `SSZ.computeDomain`.
+*/
+pub fn compute_domain(domain_type: [u8; 4], fork_version: [u8; 4], genesis_validators_root: &[u8; 32]) -> [u8; 32] {
+    let mut domain = [0u8; 32];
+    domain[..4].copy_from_slice(&domain_type);
+    domain[4..].copy_from_slice(&compute_fork_data_root(fork_version, genesis_validators_root)[..28]);
+    domain
+}
+
+/*
+* @dev hash_tree_root of `SigningData(object_root, domain)`.
+*/
+pub fn compute_signing_root(object_root: &[u8; 32], domain: &[u8; 32]) -> [u8; 32] {
+    sha256_pair(object_root, domain)
+}
+
+#[cfg(test)]
+#[allow(deprecated)]
+mod tests {
+    use super::*;
+    use cosmwasm_schema::cw_serde;
+    use cosmwasm_std::from_slice;
+
+    /// `TargetAMB.t.sol::testReceiptRootProof`, a receipts root proven against a beacon state root
+    #[cw_serde]
+    struct BranchFixture {
+        state_root: String,
+        gindex: u64,
+        receipts_root: String,
+        branch: Vec<String>,
+    }
+
+    fn root(value: &str) -> [u8; 32] {
+        hex::decode(value).unwrap().try_into().unwrap()
+    }
+
+    // zero_hashes[i], the root of a tree of 2^i zero chunks
+    const ZERO_HASH_1: &str = "f5a5fd42d16a20302798ef6ed309979b43003d2320d9f0e8ea9831a92759fb4b";
+    const ZERO_HASH_3: &str = "c78009fdf07fc56a11f122370658a353aaa542ed63e44c4bc15ff4cd105ab33c";
+
+    // Mainnet genesis block header, its root is the well known genesis block root
+    // 0x4d611d5b93fdab69013a7f0a2f961caca0c853f87cfe9595fe50038163079360
+    fn header() -> BeaconBlockHeader {
+        BeaconBlockHeader {
+            slot: 0,
+            proposer_index: 0,
+            parent_root: vec![0; 32],
+            // Genesis state root
+            state_root: hex::decode("7e76880eb67bbdc86250aa578958e9d0675e64e714337855204fb5abaaf82c2b").unwrap(),
+            // Root of an empty phase0 BeaconBlockBody
+            body_root: hex::decode("ccb62460692be0ec813b56be97f68a82cf57abc102e27bf49ebf4190ff22eedd").unwrap(),
+        }
+    }
+
+    #[test]
+    fn header_hash_tree_root() {
+        let empty = BeaconBlockHeader {
+            slot: 0,
+            proposer_index: 0,
+            parent_root: vec![0; 32],
+            state_root: vec![0; 32],
+            body_root: vec![0; 32],
+        };
+        assert_eq!(root(ZERO_HASH_3), empty.hash_tree_root().unwrap());
+
+        let header = header();
+        let header_root = header.hash_tree_root().unwrap();
+        assert_eq!(root("4d611d5b93fdab69013a7f0a2f961caca0c853f87cfe9595fe50038163079360"), header_root);
+
+        // The state root is the fourth of eight leaves
+        let body_root: [u8; 32] = header.body_root.clone().try_into().unwrap();
+        let branch = [
+            [0; 32],
+            sha256_pair(&uint64_chunk(header.slot), &uint64_chunk(header.proposer_index)),
+            sha256_pair(&sha256_pair(&body_root, &[0; 32]), &root(ZERO_HASH_1)),
+        ];
+        let state_root: [u8; 32] = header.state_root.clone().try_into().unwrap();
+        assert!(is_valid_merkle_branch(&state_root, HEADER_STATE_ROOT_INDEX, &branch, &header_root));
+
+        let mut invalid = header;
+        invalid.body_root = vec![0xcc; 31];
+        assert!(matches!(invalid.hash_tree_root(), Err(ContractError::InvalidRootLength { .. })));
+    }
+
+    #[test]
+    fn merkle_branch() {
+        let fixture: BranchFixture = from_slice(include_bytes!("../testdata/receipts_root_branch.json")).unwrap();
+        let branch: Vec<[u8; 32]> = fixture.branch.iter().map(|node| root(node)).collect();
+        let leaf = root(&fixture.receipts_root);
+        let state_root = root(&fixture.state_root);
+
+        assert_eq!(27, gindex_depth(fixture.gindex));
+        assert_eq!(state_root, restore_merkle_root(&leaf, fixture.gindex, &branch));
+        assert!(is_valid_merkle_branch(&leaf, fixture.gindex, &branch, &state_root));
+        assert!(!is_valid_merkle_branch(&leaf, fixture.gindex + 1, &branch, &state_root));
+        assert!(!is_valid_merkle_branch(&leaf, fixture.gindex, &branch[..26], &state_root));
+        assert!(!is_valid_merkle_branch(&[0; 32], fixture.gindex, &branch, &state_root));
+    }
+
+    #[test]
+    fn generalized_indices() {
+        assert_eq!(0, gindex_depth(1));
+        assert_eq!(3, gindex_depth(HEADER_STATE_ROOT_INDEX));
+        assert_eq!(6, gindex_depth(FINALIZED_ROOT_INDEX));
+        assert_eq!(5, gindex_depth(NEXT_SYNC_COMMITTEE_INDEX));
+        // The execution payload header's receipts root in the state, under a header's state root
+        assert_eq!(11 * 512 + 387, concat_gindex(HEADER_STATE_ROOT_INDEX, 899));
+        assert_eq!(concat_gindex(concat_gindex(11, 56), 19), concat_gindex(11, concat_gindex(56, 19)));
+
+        // Field, then vector element
+        assert_eq!(18, gindex_depth(block_roots_index(4359840)));
+        assert_eq!(37 * 8192 + 1696, block_roots_index(4359840));
+        // Field, list data, summary, block_summary_root, then vector element
+        let index = historical_block_root_index(3, 4359840);
+        assert_eq!(5 + 1 + 24 + 1 + 13, gindex_depth(index));
+        assert_eq!((((59 * 2) << 24 | 3) * 2) << 13 | 1696, index);
+        assert!(gindex_depth(concat_gindex(HEADER_STATE_ROOT_INDEX, index)) < 64);
+    }
+
+    #[test]
+    fn domains() {
+        // DOMAIN_DEPOSIT with GENESIS_FORK_VERSION, as used by the deposit contract
+        assert_eq!(
+            "03000000f5a5fd42d16a20302798ef6ed309979b43003d2320d9f0e8ea9831a9",
+            hex::encode(compute_domain([3, 0, 0, 0], [0; 4], &[0; 32])),
+        );
+
+        // Goerli sync committee domain after Bellatrix
+        let genesis_validators_root = root("043db0d9a83813551ee2f33450d23797757d430911a9320530ad8a0eabc43efb");
+        let domain = compute_domain(DOMAIN_SYNC_COMMITTEE, [0x02, 0x00, 0x10, 0x20], &genesis_validators_root);
+        assert_eq!("07000000c2ce3aa85707d491e3dd033a53971deb9bed9d4813d74c99369642f5", hex::encode(domain));
+
+        let header_root = header().hash_tree_root().unwrap();
+        assert_eq!(root("5c164ed66ad619f0180f69484de5fbff4741dccf9a791c6abe4fa2186c4ed367"), compute_signing_root(&header_root, &domain));
+    }
+}
@@ -1,27 +1,96 @@
 // use `cw_storage_plus` to create ORM-like interface to storage
 // see: https://crates.io/crates/cw-storage-plus
-use cosmwasm_std::{Uint256};
+use cosmwasm_std::{Addr, Empty, Uint128, Uint256};
 use cosmwasm_schema::cw_serde;
 
-use std::str::FromStr;
 
-use ark_bn254::{Bn254, Fr, G1Affine, G2Affine};
-use ark_ff::{Fp256, QuadExtField};
+use ark_bn254::{Bn254, Fr};
 use ark_groth16::Proof;
 use cw_storage_plus::{Item,Map};
 
+use crate::helpers::{affine_json_to_g1, affine_json_to_g2, str_to_fr, VerifyingKeyJson};
+use crate::ContractError;
+use crate::events::{ATTR_PERIOD, ATTR_SLOT};
+
 
 #[cw_serde]
 pub struct State {
 
     pub consistent: bool,
+    // Highest finalized slot verified so far, together with its execution state root
     pub head: Uint256,
+    pub head_execution_state_root: Vec<u8>,
+    // Steps finalizing a slot further than this behind the head are rejected, unbounded if None
+    pub max_head_lag: Option<Uint256>,
+    // Tolerated clock drift, a Step may finalize at most this many slots past the current slot
+    pub max_future_slots: Uint256,
+    // First slot of the Capella fork, where historical summaries start. Headers further back
+    // than SLOTS_PER_HISTORICAL_ROOT from a stored one can not be proven while unset
+    pub capella_fork_slot: Option<Uint256>,
 
     pub genesis_validators_root: Vec<u8>,
     pub genesis_time: Uint256,
     pub seconds_per_slot: Uint256,
     pub slots_per_period: Uint256,
 
+    // Authority for privileged operations, None once renounced
+    pub owner: Option<Addr>,
+    // Proposed owner that still has to accept the transfer
+    pub pending_owner: Option<Addr>,
+}
+
+#[cw_serde]
+pub struct VerifyingKeyVersion {
+    pub vk: VerifyingKeyJson,
+    pub activated_at_height: u64,
+}
+
+/// Native rewards paid out of the contract balance to relayers of useful updates
+#[cw_serde]
+pub struct RewardConfig {
+    pub denom: String,
+    // Paid for a Step that advances the head
+    pub step_reward: Uint128,
+    // Paid for a Rotate that sets the sync committee of a new period
+    pub rotate_reward: Uint128,
+}
+
+#[cw_serde]
+pub enum InconsistencyKind {
+    Header,
+    ExecutionStateRoot,
+    SyncCommittee,
+    HistoricalBlockRoot,
+}
+
+impl InconsistencyKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            InconsistencyKind::Header => "header",
+            InconsistencyKind::ExecutionStateRoot => "execution_state_root",
+            InconsistencyKind::SyncCommittee => "sync_committee",
+            InconsistencyKind::HistoricalBlockRoot => "historical_block_root",
+        }
+    }
+
+    // Headers, execution state roots and historical block roots are keyed by slot, sync committees by period
+    pub fn key_name(&self) -> &'static str {
+        match self {
+            InconsistencyKind::SyncCommittee => ATTR_PERIOD,
+            _ => ATTR_SLOT,
+        }
+    }
+}
+
+/// A verified root that conflicts with the one already stored for the same slot or period.
+/// Roots are recorded in the same encoding they are emitted in.
+#[cw_serde]
+pub struct InconsistencyEvidence {
+    pub kind: InconsistencyKind,
+    pub key: Uint256,
+    pub existing_root: String,
+    pub new_root: String,
+    pub submitter: Addr,
 }
 
 #[cw_serde]
@@ -33,11 +102,11 @@ pub struct Groth16Proof {
 
 #[cw_serde]
 pub struct BeaconBlockHeader {
-    slot: u64,
-    proposer_index: u64,
-    parent_root: Vec<u8>,
-    state_root: Vec<u8>,
-    body_root: Vec<u8>,
+    pub slot: u64,
+    pub proposer_index: u64,
+    pub parent_root: Vec<u8>,
+    pub state_root: Vec<u8>,
+    pub body_root: Vec<u8>,
 }
 
 #[cw_serde]
@@ -76,30 +145,11 @@ pub struct PublicSignals(pub Vec<String>);
 
 impl CircomProof {
 
-    pub fn to_proof(self) -> Proof<Bn254> {
-        let a = G1Affine::new(
-            Fp256::from_str(&self.pi_a[0]).unwrap(),
-            Fp256::from_str(&self.pi_a[1]).unwrap(),
-            false
-        );
-        let b = G2Affine::new(
-            QuadExtField::new(
-                Fp256::from_str(&self.pi_b[0][0]).unwrap(),
-                Fp256::from_str(&self.pi_b[0][1]).unwrap(),
-            ),
-            QuadExtField::new(
-                Fp256::from_str(&self.pi_b[1][0]).unwrap(),
-                Fp256::from_str(&self.pi_b[1][1]).unwrap(),
-            ),
-            false
-        );
-
-        let c = G1Affine::new(
-            Fp256::from_str(&self.pi_c[0]).unwrap(),
-            Fp256::from_str(&self.pi_c[1]).unwrap(),
-            false
-        );
-        Proof { a, b, c }
+    pub fn to_proof(self) -> Result<Proof<Bn254>, ContractError> {
+        let a = affine_json_to_g1("pi_a", &self.pi_a)?;
+        let b = affine_json_to_g2("pi_b", &self.pi_b)?;
+        let c = affine_json_to_g1("pi_c", &self.pi_c)?;
+        Ok(Proof { a, b, c })
     }
 }
 
@@ -108,19 +158,45 @@ impl PublicSignals {
         PublicSignals(public_signals)
     }
 
-    pub fn get(self) -> Vec<Fr> {
+    pub fn get(self) -> Result<Vec<Fr>, ContractError> {
         let mut inputs: Vec<Fr> = Vec::new();
-        for input in self.0 {
-            inputs.push(Fr::from_str(&input).unwrap());
+        for (i, input) in self.0.iter().enumerate() {
+            inputs.push(str_to_fr(&format!("public_signals[{}]", i), input)?);
         }
-        inputs
+        Ok(inputs)
     }
 
 }
 
-pub const HEADERS: Map<String, Vec<u8>> = Map::new("headers");
-pub const EXECUTION_STATE_ROOTS: Map<String, Vec<u8>> = Map::new("execution_state_roots");
-pub const SYNC_COMMITTEE_POSEIDONS: Map<String, Vec<u8>> = Map::new("sync_committee_poseidons");
-pub const BEST_UPDATES: Map<String, LightClientRotate> = Map::new("best_updates");
+// Keyed by slot (HEADERS, EXECUTION_STATE_ROOTS) or period, big endian so keys sort numerically
+pub const HEADERS: Map<u64, Vec<u8>> = Map::new("headers");
+pub const EXECUTION_STATE_ROOTS: Map<u64, Vec<u8>> = Map::new("execution_state_roots");
+pub const SYNC_COMMITTEE_POSEIDONS: Map<u64, Vec<u8>> = Map::new("sync_committee_poseidons");
+pub const BEST_UPDATES: Map<u64, LightClientRotate> = Map::new("best_updates");
+
+// Block roots proven with ProveHistoricalHeader, kept apart from HEADERS since the root proven
+// for a skipped slot is the one of the latest block before it
+pub const HISTORICAL_BLOCK_ROOTS: Map<u64, Vec<u8>> = Map::new("historical_block_roots");
 
 pub const STATE: Item<State> = Item::new("state");
+
+// Set by the owner to halt Step, Rotate and Force, unset when missing
+pub const PAUSED: Item<bool> = Item::new("paused");
+
+// When enabled, only allowlisted relayers may submit Step and Rotate, unset when missing
+pub const RELAYER_ALLOWLIST_ENABLED: Item<bool> = Item::new("relayer_allowlist_enabled");
+pub const RELAYERS: Map<&Addr, Empty> = Map::new("relayers");
+
+// Relayer rewards, nothing is paid while unset
+pub const REWARD_CONFIG: Item<RewardConfig> = Item::new("reward_config");
+
+// Every conflict seen since the light client was frozen, in the order they were detected
+pub const INCONSISTENCY_EVIDENCE: Item<Vec<InconsistencyEvidence>> = Item::new("inconsistency_evidence");
+
+// Active prepared verifying keys, stored raw in the encoding of `Verifier::to_bytes`
+pub const STEP_PREPARED_VERIFYING_KEY: &[u8] = b"step_prepared_verifying_key";
+pub const ROTATE_PREPARED_VERIFYING_KEY: &[u8] = b"rotate_prepared_verifying_key";
+
+// Every verifying key ever used by a circuit, keyed by version
+pub const STEP_VERIFYING_KEY_HISTORY: Map<u32, VerifyingKeyVersion> = Map::new("step_verifying_key_history");
+pub const ROTATE_VERIFYING_KEY_HISTORY: Map<u32, VerifyingKeyVersion> = Map::new("rotate_verifying_key_history");
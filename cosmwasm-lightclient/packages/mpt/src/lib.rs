@@ -0,0 +1,214 @@
+/*
+* Ethereum Merkle-Patricia trie proofs, as returned by `eth_getProof`. A port of
+* `MerklePatriciaTrie.sol` and the curve-merkle-oracle verifier it wraps, usable from
+* wasm contracts and `no_std` targets with an allocator.
+*/
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use sha3::{Digest, Keccak256};
+
+pub mod rlp;
+pub mod trie;
+
+pub use crate::rlp::Rlp;
+pub use crate::trie::extract_proof_value;
+pub use primitive_types::U256;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProofError {
+    InvalidRlp {},
+    InvalidNode { depth: usize },
+    HashMismatch { depth: usize },
+    AccountNotFound {},
+    SlotNotFound {},
+}
+
+impl fmt::Display for ProofError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProofError::InvalidRlp {} => write!(f, "Invalid RLP encoding"),
+            ProofError::InvalidNode { depth } => write!(f, "Invalid proof node at depth {}", depth),
+            ProofError::HashMismatch { depth } => write!(f, "Proof node hash mismatch at depth {}", depth),
+            ProofError::AccountNotFound {} => write!(f, "Account does not exist"),
+            ProofError::SlotNotFound {} => write!(f, "Slot value does not exist"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ProofError {}
+
+/// Account fields stored in the state trie
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountState {
+    pub nonce: u64,
+    pub balance: U256,
+    pub storage_root: [u8; 32],
+    pub code_hash: [u8; 32],
+}
+
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    Keccak256::digest(data).into()
+}
+
+/*
+* @dev Verifies an `eth_getProof` account proof against a state root and returns the
+* account. Fails with AccountNotFound if the proof shows the account does not exist.
+*/
+pub fn verify_account(proof: &[Vec<u8>], address: &[u8; 20], state_root: &[u8; 32]) -> Result<AccountState, ProofError> {
+    let value = extract_proof_value(state_root, &keccak256(address), proof)?
+        .ok_or(ProofError::AccountNotFound {})?;
+    let account = Rlp::decode(&value)?;
+    match account.as_list()? {
+        [nonce, balance, storage_root, code_hash] => Ok(AccountState {
+            nonce: nonce.as_u64()?,
+            balance: balance.as_u256()?,
+            storage_root: storage_root.as_fixed()?,
+            code_hash: code_hash.as_fixed()?,
+        }),
+        _ => Err(ProofError::InvalidRlp {}),
+    }
+}
+
+/*
+* @dev Verifies an `eth_getProof` storage proof for `slot_key`, the unhashed storage slot,
+* against an account's storage root and returns the stored word. Fails with SlotNotFound
+* if the proof shows the slot is empty.
+*/
+pub fn verify_storage(proof: &[Vec<u8>], slot_key: &[u8; 32], storage_root: &[u8; 32]) -> Result<U256, ProofError> {
+    let value = extract_proof_value(storage_root, &keccak256(slot_key), proof)?
+        .ok_or(ProofError::SlotNotFound {})?;
+    Rlp::decode(&value)?.as_u256()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    /// `eth_getProof` response, together with the state root of the block it was taken at
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct GetProof {
+        address: String,
+        state_root: String,
+        account_proof: Vec<String>,
+        balance: String,
+        code_hash: String,
+        nonce: String,
+        storage_hash: String,
+        storage_proof: Vec<StorageProof>,
+    }
+
+    #[derive(Deserialize)]
+    struct StorageProof {
+        key: String,
+        value: String,
+        proof: Vec<String>,
+    }
+
+    fn bytes(value: &str) -> Vec<u8> {
+        hex::decode(value.trim_start_matches("0x")).unwrap()
+    }
+
+    fn fixed<const N: usize>(value: &str) -> [u8; N] {
+        bytes(value).try_into().unwrap()
+    }
+
+    fn quantity(value: &str) -> U256 {
+        U256::from_str_radix(value.trim_start_matches("0x"), 16).unwrap()
+    }
+
+    fn nodes(proof: &[String]) -> Vec<Vec<u8>> {
+        proof.iter().map(|node| bytes(node)).collect()
+    }
+
+    fn fixture(json: &str) -> GetProof {
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn fixtures() -> Vec<GetProof> {
+        vec![
+            fixture(include_str!("../testdata/goerli_source_amb.json")),
+            fixture(include_str!("../testdata/ropsten.json")),
+            fixture(include_str!("../testdata/mainnet_weth.json")),
+        ]
+    }
+
+    #[test]
+    fn verify_account_fixtures() {
+        for fixture in fixtures() {
+            let account = verify_account(&nodes(&fixture.account_proof), &fixed(&fixture.address), &fixed(&fixture.state_root)).unwrap();
+            assert_eq!(AccountState {
+                nonce: quantity(&fixture.nonce).as_u64(),
+                balance: quantity(&fixture.balance),
+                storage_root: fixed(&fixture.storage_hash),
+                code_hash: fixed(&fixture.code_hash),
+            }, account);
+        }
+    }
+
+    #[test]
+    fn verify_storage_fixtures() {
+        for fixture in fixtures() {
+            for slot in fixture.storage_proof {
+                let value = verify_storage(&nodes(&slot.proof), &fixed(&slot.key), &fixed(&fixture.storage_hash)).unwrap();
+                assert_eq!(quantity(&slot.value), value);
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_proofs() {
+        let fixture = fixture(include_str!("../testdata/goerli_source_amb.json"));
+        let address = fixed(&fixture.address);
+        let state_root = fixed(&fixture.state_root);
+        let proof = nodes(&fixture.account_proof);
+
+        // Wrong root
+        assert_eq!(Err(ProofError::HashMismatch { depth: 0 }), verify_account(&proof, &address, &[0; 32]));
+
+        // Tampered intermediate node
+        let mut tampered = proof.clone();
+        tampered[3][40] ^= 1;
+        assert_eq!(Err(ProofError::HashMismatch { depth: 3 }), verify_account(&tampered, &address, &state_root));
+
+        // Truncated proof
+        assert_eq!(Err(ProofError::InvalidNode { depth: 6 }), verify_account(&proof[..7], &address, &state_root));
+
+        // Another account's path diverges from the proven leaf
+        let mut other = address;
+        other[0] ^= 1;
+        assert!(verify_account(&proof, &other, &state_root).is_err());
+
+        // Storage slot not covered by the proof
+        let slot = &fixture.storage_proof[0];
+        let storage_root = fixed(&fixture.storage_hash);
+        assert!(verify_storage(&nodes(&slot.proof), &[1; 32], &storage_root).is_err());
+    }
+
+    #[test]
+    fn empty_trie() {
+        let empty_root = keccak256(&[0x80]);
+        assert_eq!(Err(ProofError::AccountNotFound {}), verify_account(&[], &[0; 20], &empty_root));
+        assert_eq!(Err(ProofError::SlotNotFound {}), verify_storage(&[], &[0; 32], &empty_root));
+    }
+
+    #[test]
+    fn decode_rlp() {
+        assert_eq!(Rlp::Bytes(&[0x7f]), Rlp::decode(&[0x7f]).unwrap());
+        assert_eq!(Rlp::Bytes(b"dog"), Rlp::decode(&[0x83, b'd', b'o', b'g']).unwrap());
+        let list = [0xc8, 0x83, b'c', b'a', b't', 0x83, b'd', b'o', b'g'];
+        assert_eq!(Rlp::List(vec![Rlp::Bytes(b"cat"), Rlp::Bytes(b"dog")], &list), Rlp::decode(&list).unwrap());
+
+        // Trailing bytes, truncated payloads and non canonical integers
+        assert!(Rlp::decode(&[0x80, 0x00]).is_err());
+        assert!(Rlp::decode(&[0x83, b'd', b'o']).is_err());
+        assert!(Rlp::decode(&[0x82, 0x00, 0x01]).unwrap().as_u64().is_err());
+    }
+}
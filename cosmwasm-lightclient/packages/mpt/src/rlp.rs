@@ -0,0 +1,123 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use primitive_types::U256;
+
+use crate::ProofError;
+
+/*
+* Minimal RLP decoder. Items borrow from the input, lists also keep their raw encoding so
+* embedded trie nodes can be compared byte for byte.
+*/
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Rlp<'a> {
+    /// Byte string payload
+    Bytes(&'a [u8]),
+    /// List items, together with the raw encoding of the list
+    List(Vec<Rlp<'a>>, &'a [u8]),
+}
+
+impl<'a> Rlp<'a> {
+    /*
+    * @dev Decodes a single RLP item that must span the whole input.
+    */
+    pub fn decode(input: &'a [u8]) -> Result<Rlp<'a>, ProofError> {
+        let (item, rest) = decode_item(input)?;
+        if !rest.is_empty() {
+            return Err(ProofError::InvalidRlp {});
+        }
+        Ok(item)
+    }
+
+    pub fn as_bytes(&self) -> Result<&'a [u8], ProofError> {
+        match self {
+            Rlp::Bytes(bytes) => Ok(bytes),
+            Rlp::List(..) => Err(ProofError::InvalidRlp {}),
+        }
+    }
+
+    pub fn as_list(&self) -> Result<&[Rlp<'a>], ProofError> {
+        match self {
+            Rlp::List(items, _) => Ok(items),
+            Rlp::Bytes(_) => Err(ProofError::InvalidRlp {}),
+        }
+    }
+
+    /*
+    * @dev Decodes a big endian unsigned integer of at most 32 bytes without leading zeros.
+    */
+    pub fn as_u256(&self) -> Result<U256, ProofError> {
+        let bytes = self.as_uint_bytes(32)?;
+        Ok(U256::from_big_endian(bytes))
+    }
+
+    pub fn as_u64(&self) -> Result<u64, ProofError> {
+        let bytes = self.as_uint_bytes(8)?;
+        Ok(bytes.iter().fold(0u64, |acc, b| (acc << 8) | *b as u64))
+    }
+
+    /*
+    * @dev Decodes a fixed size byte string such as a hash or an address.
+    */
+    pub fn as_fixed<const N: usize>(&self) -> Result<[u8; N], ProofError> {
+        self.as_bytes()?.try_into().map_err(|_| ProofError::InvalidRlp {})
+    }
+
+    fn as_uint_bytes(&self, max: usize) -> Result<&'a [u8], ProofError> {
+        let bytes = self.as_bytes()?;
+        if bytes.len() > max || bytes.first() == Some(&0) {
+            return Err(ProofError::InvalidRlp {});
+        }
+        Ok(bytes)
+    }
+}
+
+fn split(input: &[u8], at: usize) -> Result<(&[u8], &[u8]), ProofError> {
+    if at > input.len() {
+        return Err(ProofError::InvalidRlp {});
+    }
+    Ok(input.split_at(at))
+}
+
+fn read_length(input: &[u8], len_of_len: usize) -> Result<(usize, &[u8]), ProofError> {
+    let (len_bytes, rest) = split(input, len_of_len)?;
+    if len_of_len > 8 || len_bytes.first() == Some(&0) {
+        return Err(ProofError::InvalidRlp {});
+    }
+    let len = len_bytes.iter().fold(0usize, |acc, b| (acc << 8) | *b as usize);
+    Ok((len, rest))
+}
+
+fn decode_item(input: &[u8]) -> Result<(Rlp<'_>, &[u8]), ProofError> {
+    let prefix = *input.first().ok_or(ProofError::InvalidRlp {})?;
+    let body = &input[1..];
+    match prefix {
+        0x00..=0x7f => Ok((Rlp::Bytes(&input[..1]), body)),
+        0x80..=0xb7 => {
+            let (payload, rest) = split(body, (prefix - 0x80) as usize)?;
+            Ok((Rlp::Bytes(payload), rest))
+        }
+        0xb8..=0xbf => {
+            let (len, body) = read_length(body, (prefix - 0xb7) as usize)?;
+            let (payload, rest) = split(body, len)?;
+            Ok((Rlp::Bytes(payload), rest))
+        }
+        _ => {
+            let (len, body) = if prefix <= 0xf7 {
+                ((prefix - 0xc0) as usize, body)
+            } else {
+                read_length(body, (prefix - 0xf7) as usize)?
+            };
+            let (mut payload, rest) = split(body, len)?;
+            let raw = &input[..input.len() - rest.len()];
+            let mut items = vec![];
+            while !payload.is_empty() {
+                let (item, remaining) = decode_item(payload)?;
+                items.push(item);
+                payload = remaining;
+            }
+            Ok((Rlp::List(items, raw), rest))
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use alloc::vec::Vec;
+
+use crate::rlp::Rlp;
+use crate::{keccak256, ProofError};
+
+/// How a parent node references its child: by hash, or embedded when shorter than 32 bytes
+enum NodeRef<'a> {
+    Hash([u8; 32]),
+    Inline(&'a [u8]),
+}
+
+impl<'a> NodeRef<'a> {
+    fn from_item(item: &Rlp<'a>) -> Result<Option<NodeRef<'a>>, ProofError> {
+        match item {
+            Rlp::Bytes([]) => Ok(None),
+            Rlp::Bytes(hash) if hash.len() == 32 => {
+                let mut out = [0u8; 32];
+                out.copy_from_slice(hash);
+                Ok(Some(NodeRef::Hash(out)))
+            }
+            Rlp::List(_, raw) => Ok(Some(NodeRef::Inline(raw))),
+            Rlp::Bytes(_) => Err(ProofError::InvalidRlp {}),
+        }
+    }
+
+    fn matches(&self, node: &[u8]) -> bool {
+        match self {
+            NodeRef::Hash(hash) => keccak256(node) == *hash,
+            NodeRef::Inline(raw) => *raw == node,
+        }
+    }
+}
+
+fn to_nibbles(key: &[u8]) -> Vec<u8> {
+    key.iter().flat_map(|b| [b >> 4, b & 0x0f]).collect()
+}
+
+/*
+* @dev Decodes a hex-prefix encoded path into (is_leaf, nibbles).
+*/
+fn compact_decode(path: &[u8], depth: usize) -> Result<(bool, Vec<u8>), ProofError> {
+    let nibbles = to_nibbles(path);
+    let flag = *nibbles.first().ok_or(ProofError::InvalidNode { depth })?;
+    match flag {
+        0 | 2 if nibbles.get(1) == Some(&0) => Ok((flag == 2, nibbles[2..].to_vec())),
+        1 | 3 => Ok((flag == 3, nibbles[1..].to_vec())),
+        _ => Err(ProofError::InvalidNode { depth }),
+    }
+}
+
+/*
+* @dev Walks `proof` from `root` along `key` and returns the value stored at the key, or
+* None if the proof shows the key is absent. Any node that does not hash to the reference
+* held by its parent, or a proof that continues past its terminal node, is rejected.
+*/
+pub fn extract_proof_value(root: &[u8; 32], key: &[u8], proof: &[Vec<u8>]) -> Result<Option<Vec<u8>>, ProofError> {
+    let path = to_nibbles(key);
+    let mut offset = 0;
+    let mut expected = NodeRef::Hash(*root);
+
+    if proof.is_empty() {
+        // An empty trie's root is the hash of the empty string
+        return match keccak256(&[0x80]) == *root {
+            true => Ok(None),
+            false => Err(ProofError::HashMismatch { depth: 0 }),
+        };
+    }
+
+    for (depth, encoded) in proof.iter().enumerate() {
+        let last = depth == proof.len() - 1;
+        if !expected.matches(encoded) {
+            return Err(ProofError::HashMismatch { depth });
+        }
+        let node = Rlp::decode(encoded)?;
+        let items = node.as_list()?;
+        match items.len() {
+            2 => {
+                let (is_leaf, node_path) = compact_decode(items[0].as_bytes()?, depth)?;
+                let remaining = &path[offset..];
+                if !remaining.starts_with(&node_path) {
+                    // Divergent extension or leaf proves exclusion
+                    return match last {
+                        true => Ok(None),
+                        false => Err(ProofError::InvalidNode { depth }),
+                    };
+                }
+                offset += node_path.len();
+                if is_leaf {
+                    if !last {
+                        return Err(ProofError::InvalidNode { depth });
+                    }
+                    if offset < path.len() {
+                        return Ok(None);
+                    }
+                    return Ok(Some(items[1].as_bytes()?.to_vec()));
+                }
+                if last {
+                    return Err(ProofError::InvalidNode { depth });
+                }
+                expected = NodeRef::from_item(&items[1])?.ok_or(ProofError::InvalidNode { depth })?;
+            }
+            17 => {
+                if offset == path.len() {
+                    if !last {
+                        return Err(ProofError::InvalidNode { depth });
+                    }
+                    let value = items[16].as_bytes()?;
+                    return Ok(if value.is_empty() { None } else { Some(value.to_vec()) });
+                }
+                let nibble = path[offset] as usize;
+                offset += 1;
+                match NodeRef::from_item(&items[nibble])? {
+                    Some(child) => {
+                        if last {
+                            return Err(ProofError::InvalidNode { depth });
+                        }
+                        expected = child;
+                    }
+                    None => {
+                        return match last {
+                            true => Ok(None),
+                            false => Err(ProofError::InvalidNode { depth }),
+                        };
+                    }
+                }
+            }
+            _ => return Err(ProofError::InvalidNode { depth }),
+        }
+    }
+    Err(ProofError::InvalidNode { depth: proof.len() })
+}